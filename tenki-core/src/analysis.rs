@@ -0,0 +1,242 @@
+use crate::NoteGraph;
+use petgraph::algo::{astar, tarjan_scc};
+use petgraph::graph::NodeIndex;
+use petgraph::{Direction, Undirected};
+use std::collections::{HashSet, VecDeque};
+
+/// A set of notes and the links among them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subgraph {
+    /// Notes in breadth-first order from where the subgraph was grown.
+    pub nodes: Vec<NodeIndex>,
+    /// Links between the notes, as (source, target).
+    pub edges: Vec<(NodeIndex, NodeIndex)>,
+}
+
+impl Subgraph {
+    pub fn contains(&self, idx: NodeIndex) -> bool {
+        self.nodes.contains(&idx)
+    }
+}
+
+impl NoteGraph {
+    /// Notes with no links in or out. Links from a note to itself don't count.
+    pub fn orphans(&self) -> Vec<NodeIndex> {
+        let mut notes: Vec<_> = self
+            .note_indices()
+            .filter(|&idx| {
+                self.linked(idx, Direction::Incoming) == 0
+                    && self.linked(idx, Direction::Outgoing) == 0
+            })
+            .collect();
+        self.sort_by_title(&mut notes);
+        notes
+    }
+
+    /// Notes that are linked to but don't link anywhere themselves.
+    pub fn dead_ends(&self) -> Vec<NodeIndex> {
+        let mut notes: Vec<_> = self
+            .note_indices()
+            .filter(|&idx| {
+                self.linked(idx, Direction::Outgoing) == 0
+                    && self.linked(idx, Direction::Incoming) > 0
+            })
+            .collect();
+        self.sort_by_title(&mut notes);
+        notes
+    }
+
+    /// Up to `n` notes with the most backlinks and their backlink counts,
+    /// most linked first. Notes without backlinks are left out.
+    pub fn hubs(&self, n: usize) -> Vec<(NodeIndex, usize)> {
+        let mut notes: Vec<_> = self
+            .note_indices()
+            .map(|idx| (idx, self.linked(idx, Direction::Incoming)))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        notes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| self.title_cmp(a.0, b.0)));
+        notes.truncate(n);
+        notes
+    }
+
+    /// Groups of notes that can all reach each other by following links,
+    /// largest first. Notes not part of any cycle are left out.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeIndex>> {
+        let mut components: Vec<_> = tarjan_scc(self.inner())
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|mut component| {
+                self.sort_by_title(&mut component);
+                component
+            })
+            .collect();
+        components.sort_by(|a, b| {
+            b.len()
+                .cmp(&a.len())
+                .then_with(|| self.title_cmp(a[0], b[0]))
+        });
+        components
+    }
+
+    /// The shortest chain of links from `from` to `to`, both included,
+    /// following links only in the direction they point.
+    pub fn shortest_path(&self, from: NodeIndex, to: NodeIndex) -> Option<Vec<NodeIndex>> {
+        astar(self.inner(), from, |n| n == to, |_| 1, |_| 0).map(|(_, path)| path)
+    }
+
+    /// Like [`shortest_path`](Self::shortest_path), but links can be followed
+    /// either way, so it finds how two notes relate even without a chain
+    /// of outgoing links.
+    pub fn shortest_path_undirected(
+        &self,
+        from: NodeIndex,
+        to: NodeIndex,
+    ) -> Option<Vec<NodeIndex>> {
+        // Mapping keeps node indices, so the path applies to this graph
+        let undirected = self
+            .inner()
+            .map(|_, _| (), |_, _| ())
+            .into_edge_type::<Undirected>();
+        astar(&undirected, from, |n| n == to, |_| 1, |_| 0).map(|(_, path)| path)
+    }
+
+    /// Notes within `depth` links of `idx` in either direction, and the links
+    /// among them.
+    pub fn neighborhood(&self, idx: NodeIndex, depth: usize) -> Subgraph {
+        let graph = self.inner();
+        if graph.node_weight(idx).is_none() {
+            return Subgraph::default();
+        }
+        let mut seen = HashSet::from([idx]);
+        let mut nodes = vec![idx];
+        let mut queue = VecDeque::from([(idx, 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            for next in graph.neighbors_undirected(current) {
+                if seen.insert(next) {
+                    nodes.push(next);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        let edges = graph
+            .edge_indices()
+            .filter_map(|e| graph.edge_endpoints(e))
+            .filter(|(a, b)| seen.contains(a) && seen.contains(b))
+            .collect();
+        Subgraph { nodes, edges }
+    }
+
+    /// Number of distinct other notes linked in the given direction.
+    fn linked(&self, idx: NodeIndex, direction: Direction) -> usize {
+        self.inner()
+            .neighbors_directed(idx, direction)
+            .filter(|&other| other != idx)
+            .count()
+    }
+
+    fn title_cmp(&self, a: NodeIndex, b: NodeIndex) -> std::cmp::Ordering {
+        let title = |idx| self.get_note(idx).map(|note| note.title.to_lowercase());
+        title(a).cmp(&title(b))
+    }
+
+    fn sort_by_title(&self, notes: &mut [NodeIndex]) {
+        notes.sort_by(|&a, &b| self.title_cmp(a, b));
+    }
+}
+
+/// A summary of the vault's link structure.
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    pub notes: usize,
+    pub links: usize,
+    pub orphans: Vec<NodeIndex>,
+    pub dead_ends: Vec<NodeIndex>,
+    pub hubs: Vec<(NodeIndex, usize)>,
+    pub components: Vec<Vec<NodeIndex>>,
+}
+
+impl HealthReport {
+    /// How many hubs a report lists.
+    pub const HUBS: usize = 10;
+
+    pub fn new(graph: &NoteGraph) -> Self {
+        Self {
+            notes: graph.note_count(),
+            links: graph.link_count(),
+            orphans: graph.orphans(),
+            dead_ends: graph.dead_ends(),
+            hubs: graph.hubs(Self::HUBS),
+            components: graph.strongly_connected_components(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_health_report() {
+        let mut graph = NoteGraph::new();
+        let mut add = |name: &str| graph.add_note(PathBuf::from(format!("{name}.md")), name.into());
+        let [a, b, c, d, e, f] = ["A", "B", "C", "D", "E", "F"].map(&mut add);
+
+        // A <-> B -> C <- D, D links to itself, E <-> F, and G only links to itself
+        graph.add_link(a, b);
+        graph.add_link(b, a);
+        graph.add_link(b, c);
+        graph.add_link(d, c);
+        graph.add_link(d, d);
+        graph.add_link(e, f);
+        graph.add_link(f, e);
+        let lonely = graph.add_note(PathBuf::from("g.md"), "G".into());
+        graph.add_link(lonely, lonely);
+
+        assert_eq!(graph.orphans(), vec![lonely]);
+        assert_eq!(graph.dead_ends(), vec![c]);
+        assert_eq!(graph.hubs(2), vec![(c, 2), (a, 1)]);
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![vec![a, b], vec![e, f]]
+        );
+
+        let report = HealthReport::new(&graph);
+        assert_eq!(report.notes, 7);
+        assert_eq!(report.links, 8);
+        assert_eq!(report.hubs.len(), 5);
+    }
+
+    #[test]
+    fn test_paths_and_neighborhood() {
+        let mut graph = NoteGraph::new();
+        let mut add = |name: &str| graph.add_note(PathBuf::from(format!("{name}.md")), name.into());
+        let [a, b, c, d, e] = ["A", "B", "C", "D", "E"].map(&mut add);
+
+        // A -> B -> C <- D, E on its own
+        graph.add_link(a, b);
+        graph.add_link(b, c);
+        graph.add_link(d, c);
+
+        assert_eq!(graph.shortest_path(a, c), Some(vec![a, b, c]));
+        assert_eq!(graph.shortest_path(c, a), None);
+        assert_eq!(graph.shortest_path(a, d), None);
+        assert_eq!(graph.shortest_path_undirected(a, d), Some(vec![a, b, c, d]));
+        assert_eq!(graph.shortest_path_undirected(a, e), None);
+        assert_eq!(graph.shortest_path(a, a), Some(vec![a]));
+
+        let near = graph.neighborhood(c, 1);
+        assert_eq!(near.nodes[0], c);
+        assert_eq!(near.nodes.len(), 3);
+        assert!(near.contains(b) && near.contains(d) && !near.contains(a));
+        assert_eq!(near.edges.len(), 2);
+
+        let wider = graph.neighborhood(c, 2);
+        assert_eq!(wider.nodes.len(), 4);
+        assert_eq!(wider.edges.len(), 3);
+        assert_eq!(graph.neighborhood(e, 3).nodes, vec![e]);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::parser::Task;
+use crate::{NoteGraph, Period, PeriodicNote};
+use chrono::{DateTime, Local, NaiveDate};
+use petgraph::graph::NodeIndex;
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+/// What happened in the vault on one day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DayActivity {
+    /// The day's daily note.
+    pub journal: Option<NodeIndex>,
+    pub created: Vec<NodeIndex>,
+    /// Notes modified that day, other than those created on it.
+    pub modified: Vec<NodeIndex>,
+    /// Open tasks due that day, with the note they are in.
+    pub due: Vec<(NodeIndex, Task)>,
+}
+
+impl DayActivity {
+    pub fn is_empty(&self) -> bool {
+        self.journal.is_none()
+            && self.created.is_empty()
+            && self.modified.is_empty()
+            && self.due.is_empty()
+    }
+
+    /// Every note the day touches, each once: the daily note, then notes
+    /// created, modified and with tasks due.
+    pub fn notes(&self) -> Vec<NodeIndex> {
+        let mut notes: Vec<NodeIndex> = Vec::new();
+        let all = self
+            .journal
+            .iter()
+            .chain(&self.created)
+            .chain(&self.modified);
+        for &idx in all.chain(self.due.iter().map(|(idx, _)| idx)) {
+            if !notes.contains(&idx) {
+                notes.push(idx);
+            }
+        }
+        notes
+    }
+}
+
+fn local_date(time: Option<SystemTime>) -> Option<NaiveDate> {
+    time.map(|time| DateTime::<Local>::from(time).date_naive())
+}
+
+impl NoteGraph {
+    /// Activity for each day from `from` to `to`, inclusive. Days with none
+    /// are left out.
+    pub fn activity(&self, from: NaiveDate, to: NaiveDate) -> BTreeMap<NaiveDate, DayActivity> {
+        let mut days: BTreeMap<NaiveDate, DayActivity> = BTreeMap::new();
+        let in_range = |date: &NaiveDate| (from..=to).contains(date);
+        for idx in self.note_indices() {
+            let Some(note) = self.get_note(idx) else {
+                continue;
+            };
+            if let Some(journal) = PeriodicNote::parse(&note.title)
+                && journal.period == Period::Day
+                && in_range(&journal.start)
+            {
+                days.entry(journal.start).or_default().journal = Some(idx);
+            }
+            let created = local_date(note.meta.created);
+            if let Some(date) = created.filter(in_range) {
+                days.entry(date).or_default().created.push(idx);
+            }
+            if let Some(date) = local_date(note.meta.modified).filter(in_range)
+                && created != Some(date)
+            {
+                days.entry(date).or_default().modified.push(idx);
+            }
+            for task in &note.meta.tasks {
+                if !task.done
+                    && let Some(date) = task.due.filter(in_range)
+                {
+                    days.entry(date).or_default().due.push((idx, task.clone()));
+                }
+            }
+        }
+        // Note indices aren't in any meaningful order
+        for day in days.values_mut() {
+            let title = |idx: &NodeIndex| {
+                self.get_note(*idx)
+                    .map(|note| note.title.to_lowercase())
+                    .unwrap_or_default()
+            };
+            day.created.sort_by_cached_key(title);
+            day.modified.sort_by_cached_key(title);
+            day.due
+                .sort_by_cached_key(|(idx, task)| (title(idx), task.line));
+        }
+        days
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::NoteMeta;
+    use crate::parser::extract_tasks;
+    use chrono::TimeZone;
+    use std::path::PathBuf;
+
+    fn time(d: u32, h: u32) -> Option<SystemTime> {
+        Some(Local.with_ymd_and_hms(2026, 10, d, h, 0, 0).unwrap().into())
+    }
+
+    #[test]
+    fn test_activity() {
+        let mut graph = NoteGraph::new();
+        let journal = graph.add_note(PathBuf::from("journal/2026-10-17.md"), "2026-10-17".into());
+        let old = graph.add_note(PathBuf::from("old.md"), "Old".into());
+        graph.set_meta(
+            old,
+            NoteMeta {
+                created: time(1, 9),
+                modified: time(17, 9),
+                tasks: extract_tasks("- [ ] Pay rent due:2026-10-20\n- [x] Done due:2026-10-20\n"),
+                ..Default::default()
+            },
+        );
+        let new = graph.add_note(PathBuf::from("new.md"), "New".into());
+        graph.set_meta(
+            new,
+            NoteMeta {
+                created: time(17, 10),
+                modified: time(17, 11),
+                ..Default::default()
+            },
+        );
+
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let activity = graph.activity(day(10), day(31));
+        assert_eq!(
+            activity.keys().copied().collect::<Vec<_>>(),
+            vec![day(17), day(20)]
+        );
+
+        let saturday = &activity[&day(17)];
+        assert_eq!(saturday.journal, Some(journal));
+        assert_eq!(saturday.created, vec![new]);
+        assert_eq!(saturday.modified, vec![old]);
+        assert_eq!(saturday.notes(), vec![journal, new, old]);
+
+        let tuesday = &activity[&day(20)];
+        assert_eq!(tuesday.due.len(), 1);
+        assert_eq!(tuesday.due[0].1.text, "Pay rent due:2026-10-20");
+        assert_eq!(tuesday.notes(), vec![old]);
+        assert!(!tuesday.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::NoteGraph;
+use crate::fuzzy::fuzzy_match;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// A wikilink being typed: the text after an unclosed `[[` before the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkQuery {
+    /// Byte offset just after the `[[`.
+    pub start: usize,
+    /// What has been typed of the target so far.
+    pub target: String,
+    /// What has been typed after `#`, when completing a heading.
+    pub heading: Option<String>,
+}
+
+impl LinkQuery {
+    /// The link being typed at byte offset `cursor` of `text`, if any. Only
+    /// the current line is looked at, and typing an alias after `|` doesn't
+    /// count.
+    pub fn at(text: &str, cursor: usize) -> Option<Self> {
+        let before = text.get(..cursor)?;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let start = before[line_start..].rfind("[[")? + line_start + 2;
+        let typed = &before[start..];
+        if typed.contains("]]") || typed.contains('|') || typed.contains('[') {
+            return None;
+        }
+        let (target, heading) = match typed.split_once('#') {
+            Some((target, heading)) => (target, Some(heading.to_string())),
+            None => (typed, None),
+        };
+        Some(Self {
+            start,
+            target: target.to_string(),
+            heading,
+        })
+    }
+}
+
+/// What a completion links to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionKind {
+    Title,
+    /// One of the note's aliases; the link shows the alias.
+    Alias,
+    Heading,
+}
+
+/// A suggestion for the link being typed.
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub kind: CompletionKind,
+    /// The title, alias or heading matched.
+    pub label: String,
+    /// Title of the note linked to.
+    pub title: String,
+    pub path: PathBuf,
+    /// Text to put in place of everything from [`LinkQuery::start`] to the
+    /// cursor, including the closing `]]`.
+    pub insert: String,
+    /// Byte offsets of the matched characters in `label`.
+    pub positions: Vec<usize>,
+    pub score: i64,
+}
+
+/// Bonus for notes touched recently, so what is being worked on comes first.
+fn recency_bonus(modified: Option<SystemTime>, now: SystemTime) -> i64 {
+    const DAY: u64 = 24 * 60 * 60;
+    let Some(age) = modified.and_then(|m| now.duration_since(m).ok()) else {
+        return 0;
+    };
+    if age < Duration::from_secs(DAY) {
+        15
+    } else if age < Duration::from_secs(7 * DAY) {
+        8
+    } else if age < Duration::from_secs(30 * DAY) {
+        3
+    } else {
+        0
+    }
+}
+
+impl NoteGraph {
+    /// Suggestions for a link being typed, best first: note titles and
+    /// aliases, or the headings of the named note after `#`. Matches are
+    /// fuzzy, with recently modified notes ranked higher.
+    pub fn complete_link(&self, query: &LinkQuery, limit: usize) -> Vec<Completion> {
+        let mut completions = match &query.heading {
+            Some(heading) => self.complete_heading(&query.target, heading),
+            None => self.complete_note(&query.target),
+        };
+        completions.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.label.to_lowercase().cmp(&b.label.to_lowercase()))
+        });
+        completions.truncate(limit);
+        completions
+    }
+
+    fn complete_note(&self, typed: &str) -> Vec<Completion> {
+        let now = SystemTime::now();
+        self.all_notes()
+            .into_iter()
+            .filter_map(|note| {
+                let bonus = recency_bonus(note.meta.modified, now);
+                let title = fuzzy_match(typed, &note.title)
+                    .map(|m| (CompletionKind::Title, note.title.as_str(), m));
+                let aliases = note.aliases.iter().filter_map(|alias| {
+                    fuzzy_match(typed, alias).map(|m| (CompletionKind::Alias, alias.as_str(), m))
+                });
+                // An alias only shows when it matches better than the title
+                title
+                    .into_iter()
+                    .chain(aliases)
+                    .max_by_key(|(kind, _, m)| (m.score, *kind == CompletionKind::Title))
+                    .map(|(kind, label, m)| Completion {
+                        insert: match kind {
+                            CompletionKind::Alias => format!("{}|{label}]]", note.title),
+                            _ => format!("{}]]", note.title),
+                        },
+                        kind,
+                        label: label.to_string(),
+                        title: note.title.clone(),
+                        path: note.path.clone(),
+                        positions: m.positions,
+                        score: m.score + bonus,
+                    })
+            })
+            .collect()
+    }
+
+    fn complete_heading(&self, target: &str, typed: &str) -> Vec<Completion> {
+        let Some(note) = self
+            .find_by_title(target.trim())
+            .and_then(|idx| self.get_note(idx))
+        else {
+            return Vec::new();
+        };
+        let count = note.meta.headings.len() as i64;
+        note.meta
+            .headings
+            .iter()
+            .enumerate()
+            .filter_map(|(i, heading)| {
+                let m = fuzzy_match(typed, heading)?;
+                Some(Completion {
+                    kind: CompletionKind::Heading,
+                    label: heading.clone(),
+                    title: note.title.clone(),
+                    path: note.path.clone(),
+                    insert: format!("{}#{heading}]]", note.title),
+                    positions: m.positions,
+                    // Keep document order among equal matches
+                    score: m.score * count + (count - i as i64),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::NoteMeta;
+
+    #[test]
+    fn test_link_query() {
+        assert_eq!(
+            LinkQuery::at("See [[Wiki", 10),
+            Some(LinkQuery {
+                start: 6,
+                target: "Wiki".into(),
+                heading: None,
+            })
+        );
+        let query = LinkQuery::at("x\n[[Keyboard Shortcuts#Act", 26).unwrap();
+        assert_eq!(query.start, 4);
+        assert_eq!(query.target, "Keyboard Shortcuts");
+        assert_eq!(query.heading.as_deref(), Some("Act"));
+        assert_eq!(LinkQuery::at("[[", 2).unwrap().target, "");
+
+        assert_eq!(LinkQuery::at("[[Done]] and", 12), None);
+        assert_eq!(LinkQuery::at("[[Title|ali", 11), None);
+        assert_eq!(LinkQuery::at("[[Broken\nnext", 13), None);
+        assert_eq!(LinkQuery::at("no link", 7), None);
+    }
+
+    #[test]
+    fn test_complete_link() {
+        let now = SystemTime::now();
+        let mut graph = NoteGraph::new();
+        let old = graph.add_note(PathBuf::from("old.md"), "Weekly Review".into());
+        graph.set_meta(
+            old,
+            NoteMeta {
+                modified: Some(now - Duration::from_secs(90 * 24 * 60 * 60)),
+                headings: vec!["Weekly Review".into(), "Wins".into(), "Next week".into()],
+                ..Default::default()
+            },
+        );
+        let new = graph.add_note(PathBuf::from("new.md"), "Weekly Plan".into());
+        graph.set_meta(
+            new,
+            NoteMeta {
+                modified: Some(now),
+                ..Default::default()
+            },
+        );
+        let syntax = graph.add_note(PathBuf::from("syntax.md"), "Markdown Syntax".into());
+        graph.add_alias(syntax, "md".into());
+
+        let query = LinkQuery::at("[[week", 6).unwrap();
+        let titles: Vec<String> = graph
+            .complete_link(&query, 10)
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        // Equally good matches, but the plan was touched today
+        assert_eq!(titles, vec!["Weekly Plan", "Weekly Review"]);
+
+        let query = LinkQuery::at("[[md", 4).unwrap();
+        let completion = &graph.complete_link(&query, 1)[0];
+        assert_eq!(completion.kind, CompletionKind::Alias);
+        assert_eq!(completion.insert, "Markdown Syntax|md]]");
+
+        let query = LinkQuery::at("[[weekly review#w", 17).unwrap();
+        let headings: Vec<String> = graph
+            .complete_link(&query, 10)
+            .into_iter()
+            .map(|c| c.insert)
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                "Weekly Review#Wins]]",
+                "Weekly Review#Weekly Review]]",
+                "Weekly Review#Next week]]"
+            ]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{Error, NoteGraph, Query, Result, SortOrder};
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory inside the vault holding tenki's own files.
+pub const TENKI_DIR: &str = ".tenki";
+
+/// A named query shown as a virtual folder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+}
+
+impl SavedSearch {
+    /// Whether the search is called `name`, ignoring case as links do.
+    fn is_named(&self, name: &str) -> bool {
+        self.name.to_lowercase() == name.to_lowercase()
+    }
+
+    /// Parse and run the saved query.
+    pub fn run(&self, graph: &NoteGraph) -> Result<Vec<NodeIndex>> {
+        Ok(Query::parse(&self.query)?.run(graph))
+    }
+}
+
+/// Per-vault settings stored in `.tenki/config.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VaultConfig {
+    /// How the file list is ordered.
+    pub sort: SortOrder,
+    pub saved_searches: Vec<SavedSearch>,
+}
+
+impl VaultConfig {
+    /// Location of the config file for a vault.
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(TENKI_DIR).join("config.toml")
+    }
+
+    /// Load the vault config, or the defaults if there is none yet.
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = Self::path(root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path)?;
+        toml::from_str(&text).map_err(|e| Error::Config(e.to_string()))
+    }
+
+    /// Write the config back to the vault.
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::path(root);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Find a saved search by name (case-insensitive).
+    pub fn saved_search(&self, name: &str) -> Option<&SavedSearch> {
+        self.saved_searches.iter().find(|s| s.is_named(name))
+    }
+
+    /// Add or replace a saved search. The query must parse.
+    pub fn save_search(&mut self, name: &str, query: &str) -> Result<()> {
+        Query::parse(query)?;
+        let search = SavedSearch {
+            name: name.trim().to_string(),
+            query: query.trim().to_string(),
+        };
+        match self.saved_searches.iter_mut().find(|s| s.is_named(name)) {
+            Some(existing) => *existing = search,
+            None => self.saved_searches.push(search),
+        }
+        Ok(())
+    }
+
+    /// Remove a saved search, returning it if it existed.
+    pub fn remove_search(&mut self, name: &str) -> Option<SavedSearch> {
+        let pos = self.saved_searches.iter().position(|s| s.is_named(name))?;
+        Some(self.saved_searches.remove(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Scanner, SortKey};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_saved_searches_roundtrip() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.md"), "# A\n\n#todo item")?;
+        fs::write(root.join("b.md"), "# B\n\nnothing")?;
+
+        assert_eq!(VaultConfig::load(root)?, VaultConfig::default());
+
+        let mut config = VaultConfig {
+            sort: SortOrder {
+                key: SortKey::Modified,
+                descending: true,
+            },
+            ..VaultConfig::default()
+        };
+        config.save_search("Todo", "tag:todo")?;
+        config.save_search("todo", "tag:#todo")?;
+        assert!(config.save_search("Broken", "(tag:x").is_err());
+        config.save(root)?;
+
+        let config = VaultConfig::load(root)?;
+        assert_eq!(config.sort.key, SortKey::Modified);
+        assert!(config.sort.descending);
+        assert_eq!(config.saved_searches.len(), 1);
+        let search = config.saved_search("TODO").unwrap();
+        assert_eq!(search.query, "tag:#todo");
+
+        let graph = Scanner::new(root).scan()?;
+        let results = search.run(&graph)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(graph.get_note(results[0]).unwrap().title, "A");
+
+        let mut config = config;
+        config.save_search("Ärger", "tag:todo")?;
+        config.save_search("ärger", "tag:#todo")?;
+        assert_eq!(config.saved_searches.len(), 2);
+        assert_eq!(config.saved_search("ÄRGER").unwrap().query, "tag:#todo");
+        assert!(config.remove_search("ärger").is_some());
+        assert!(config.remove_search("todo").is_some());
+        assert!(config.saved_searches.is_empty());
+        Ok(())
+    }
+}
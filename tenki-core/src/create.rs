@@ -0,0 +1,168 @@
+use crate::frontmatter::Frontmatter;
+use crate::{Error, NoteGraph, Result, Scanner};
+use petgraph::graph::NodeIndex;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Turn a title into a file name stem: lowercase words joined by `_`, keeping
+/// letters and digits in any script and dropping other punctuation.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    let mut separate = false;
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            if separate && !slug.is_empty() {
+                slug.push('_');
+            }
+            separate = false;
+            slug.extend(c.to_lowercase());
+        } else if c.is_whitespace() || c == '-' || c == '_' {
+            separate = true;
+        }
+    }
+    if slug.is_empty() {
+        slug.push_str("untitled");
+    }
+    slug
+}
+
+/// Check that `folder` is a relative path that stays inside the vault.
+pub(crate) fn check_folder(folder: &Path) -> Result<()> {
+    if folder
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(())
+    } else {
+        Err(Error::InvalidPath(folder.display().to_string()))
+    }
+}
+
+impl Scanner {
+    /// Where a note titled `title` would be created in `folder`, relative to
+    /// the root.
+    pub fn note_path(&self, folder: &Path, title: &str) -> Result<PathBuf> {
+        check_folder(folder)?;
+        Ok(self
+            .root()
+            .join(folder)
+            .join(format!("{}.md", slugify(title))))
+    }
+
+    /// Write a new note titled `title` into `folder` (relative to the root,
+    /// created if missing) and add it to the graph. Links elsewhere in the
+    /// vault that were waiting for this title resolve to it.
+    pub fn create_note(
+        &self,
+        graph: &mut NoteGraph,
+        folder: &Path,
+        title: &str,
+    ) -> Result<NodeIndex> {
+        self.create_note_from(graph, folder, title, "")
+    }
+
+    /// Like [`Scanner::create_note`], starting the note with `content`, e.g.
+    /// a rendered template. A `# ` heading with the title is added after any
+    /// frontmatter unless the content has one.
+    pub fn create_note_from(
+        &self,
+        graph: &mut NoteGraph,
+        folder: &Path,
+        title: &str,
+        content: &str,
+    ) -> Result<NodeIndex> {
+        let path = self.note_path(folder, title)?;
+        self.create_note_at(graph, &path, title, content)
+    }
+
+    /// Write a new note to `path`, which must be inside the root.
+    pub(crate) fn create_note_at(
+        &self,
+        graph: &mut NoteGraph,
+        path: &Path,
+        title: &str,
+        content: &str,
+    ) -> Result<NodeIndex> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(Error::InvalidPath("empty title".to_string()));
+        }
+        if graph.find_by_title(title).is_some() {
+            return Err(Error::NoteExists(title.to_string()));
+        }
+        if path.exists() {
+            return Err(Error::NoteExists(path.display().to_string()));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let has_heading = content.lines().any(|line| line.trim().starts_with("# "));
+        let content = if has_heading {
+            content.to_string()
+        } else {
+            let start = Frontmatter::parse(content).map_or(0, |f| f.end);
+            format!("{}# {title}\n\n{}", &content[..start], &content[start..])
+        };
+        fs::write(path, content)?;
+
+        let idx = self.update_file(graph, path)?;
+        graph.update_ranking();
+        Ok(idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Meeting Notes"), "meeting_notes");
+        assert_eq!(slugify("  What's next?  "), "whats_next");
+        assert_eq!(slugify("Rust -- async_await"), "rust_async_await");
+        assert_eq!(slugify("天気 メモ"), "天気_メモ");
+        assert_eq!(slugify("!!!"), "untitled");
+    }
+
+    #[test]
+    fn test_create_note() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("index.md"), "# Index\n\nSee [[New Idea]].")?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        let index = graph.find_by_title("Index").unwrap();
+        assert_eq!(graph.unresolved_links(index).len(), 1);
+
+        let idx = scanner.create_note(&mut graph, Path::new("ideas"), "New Idea")?;
+        let path = root.join("ideas/new_idea.md");
+        assert_eq!(fs::read_to_string(&path)?, "# New Idea\n\n");
+        assert_eq!(graph.get_note(idx).unwrap().path, path);
+        assert!(graph.unresolved_links(index).is_empty());
+        assert!(graph.has_link(index, idx));
+
+        assert!(matches!(
+            scanner.create_note(&mut graph, Path::new(""), "new idea"),
+            Err(Error::NoteExists(_))
+        ));
+        assert!(matches!(
+            scanner.create_note(&mut graph, Path::new("../outside"), "Elsewhere"),
+            Err(Error::InvalidPath(_))
+        ));
+
+        let idx = scanner.create_note_from(
+            &mut graph,
+            Path::new(""),
+            "Tagged",
+            "---\ntags: [a]\n---\nBody\n",
+        )?;
+        assert_eq!(
+            fs::read_to_string(root.join("tagged.md"))?,
+            "---\ntags: [a]\n---\n# Tagged\n\nBody\n"
+        );
+        assert!(graph.get_note(idx).unwrap().has_tag("a"));
+        Ok(())
+    }
+}
@@ -8,8 +8,28 @@ pub enum Error {
     #[error("Note not found: {0}")]
     NoteNotFound(String),
 
+    #[error("Note already exists: {0}")]
+    NoteExists(String),
+
     #[error("Invalid path: {0}")]
     InvalidPath(String),
+
+    #[error("File changed since it was scanned: {0}")]
+    StaleSpan(String),
+
+    /// A note being edited was changed on disk by something else since
+    /// editing started, so saving would overwrite that change.
+    #[error("Note changed on disk since it was opened: {0}")]
+    EditConflict(String),
+
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    #[error("Invalid config: {0}")]
+    Config(String),
+
+    #[error("Unknown export format: {0}")]
+    UnknownFormat(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,365 @@
+use crate::{Error, NoteGraph, Result, Subgraph};
+use petgraph::graph::NodeIndex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+/// File formats the graph can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Graphviz DOT.
+    Dot,
+    /// GraphML, for Gephi, yEd and similar tools.
+    GraphMl,
+    /// A JSON object with `nodes` and `edges` arrays.
+    Json,
+    /// A Mermaid flowchart.
+    Mermaid,
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
+            "json" => Ok(Self::Json),
+            "mermaid" | "mmd" => Ok(Self::Mermaid),
+            _ => Err(Error::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Which notes to export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportScope {
+    All,
+    /// Notes within `depth` links of `center`.
+    Neighborhood {
+        center: NodeIndex,
+        depth: usize,
+    },
+    /// Notes with a tag, `#` optional.
+    Tag(String),
+}
+
+#[derive(Serialize)]
+struct JsonGraph<'a> {
+    nodes: &'a [ExportNode<'a>],
+    edges: Vec<JsonEdge>,
+}
+
+#[derive(Serialize)]
+struct JsonEdge {
+    source: String,
+    target: String,
+    /// How many times the source links to the target.
+    count: usize,
+}
+
+/// A note as it appears in an export.
+#[derive(Serialize)]
+struct ExportNode<'a> {
+    /// Path relative to the vault root, with `/` separators.
+    id: String,
+    title: &'a str,
+    tags: &'a [String],
+}
+
+impl NoteGraph {
+    /// The notes and links covered by a scope.
+    pub fn subgraph(&self, scope: &ExportScope) -> Subgraph {
+        let nodes: Vec<NodeIndex> = match scope {
+            ExportScope::All => self.note_indices().collect(),
+            ExportScope::Neighborhood { center, depth } => {
+                return self.neighborhood(*center, *depth);
+            }
+            ExportScope::Tag(tag) => self
+                .note_indices()
+                .filter(|&idx| self.get_note(idx).is_some_and(|n| n.has_tag(tag)))
+                .collect(),
+        };
+        let members: HashSet<NodeIndex> = nodes.iter().copied().collect();
+        let edges = self
+            .inner()
+            .edge_indices()
+            .filter_map(|e| self.inner().edge_endpoints(e))
+            .filter(|(a, b)| members.contains(a) && members.contains(b))
+            .collect();
+        Subgraph { nodes, edges }
+    }
+
+    /// Render part of the graph in an external format. Note ids are paths
+    /// relative to `root`; nodes and edges are sorted so output is stable.
+    pub fn export(&self, format: ExportFormat, scope: &ExportScope, root: &Path) -> String {
+        let subgraph = self.subgraph(scope);
+        let mut nodes: Vec<(NodeIndex, ExportNode)> = subgraph
+            .nodes
+            .iter()
+            .filter_map(|&idx| {
+                let note = self.get_note(idx)?;
+                let relative = note.path.strip_prefix(root).unwrap_or(&note.path);
+                let id = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                Some((
+                    idx,
+                    ExportNode {
+                        id,
+                        title: &note.title,
+                        tags: &note.meta.tags,
+                    },
+                ))
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.1.id.cmp(&b.1.id));
+        let position: HashMap<NodeIndex, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (idx, _))| (*idx, i))
+            .collect();
+
+        // (source, target, occurrences) as positions in `nodes`
+        let mut edges: Vec<(usize, usize, usize)> = subgraph
+            .edges
+            .iter()
+            .filter_map(|(a, b)| {
+                let edge = self.inner().find_edge(*a, *b)?;
+                let count = self.inner()[edge].len().max(1);
+                Some((*position.get(a)?, *position.get(b)?, count))
+            })
+            .collect();
+        edges.sort();
+
+        let nodes: Vec<ExportNode> = nodes.into_iter().map(|(_, node)| node).collect();
+        match format {
+            ExportFormat::Dot => to_dot(&nodes, &edges),
+            ExportFormat::GraphMl => to_graphml(&nodes, &edges),
+            ExportFormat::Json => to_json(&nodes, &edges),
+            ExportFormat::Mermaid => to_mermaid(&nodes, &edges),
+        }
+    }
+}
+
+fn to_dot(nodes: &[ExportNode], edges: &[(usize, usize, usize)]) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut out = String::from("digraph tenki {\n    node [shape=box];\n");
+    for node in nodes {
+        let _ = writeln!(
+            out,
+            "    {} [label={}];",
+            quote(&node.id),
+            quote(node.title)
+        );
+    }
+    for &(a, b, _) in edges {
+        let _ = writeln!(
+            out,
+            "    {} -> {};",
+            quote(&nodes[a].id),
+            quote(&nodes[b].id)
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn to_graphml(nodes: &[ExportNode], edges: &[(usize, usize, usize)]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+         <key id=\"title\" for=\"node\" attr.name=\"title\" attr.type=\"string\"/>\n  \
+         <key id=\"tags\" for=\"node\" attr.name=\"tags\" attr.type=\"string\"/>\n  \
+         <key id=\"count\" for=\"edge\" attr.name=\"count\" attr.type=\"int\"/>\n  \
+         <graph id=\"tenki\" edgedefault=\"directed\">\n",
+    );
+    for node in nodes {
+        let _ = writeln!(out, "    <node id=\"{}\">", xml_escape(&node.id));
+        let _ = writeln!(
+            out,
+            "      <data key=\"title\">{}</data>",
+            xml_escape(node.title)
+        );
+        if !node.tags.is_empty() {
+            let _ = writeln!(
+                out,
+                "      <data key=\"tags\">{}</data>",
+                xml_escape(&node.tags.join(" "))
+            );
+        }
+        out.push_str("    </node>\n");
+    }
+    for &(a, b, count) in edges {
+        let _ = writeln!(
+            out,
+            "    <edge source=\"{}\" target=\"{}\">\n      <data key=\"count\">{count}</data>\n    </edge>",
+            xml_escape(&nodes[a].id),
+            xml_escape(&nodes[b].id)
+        );
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn to_json(nodes: &[ExportNode], edges: &[(usize, usize, usize)]) -> String {
+    let graph = JsonGraph {
+        nodes,
+        edges: edges
+            .iter()
+            .map(|&(a, b, count)| JsonEdge {
+                source: nodes[a].id.clone(),
+                target: nodes[b].id.clone(),
+                count,
+            })
+            .collect(),
+    };
+    // Serialising plain strings and numbers can't fail
+    let mut out = serde_json::to_string_pretty(&graph).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+fn to_mermaid(nodes: &[ExportNode], edges: &[(usize, usize, usize)]) -> String {
+    let mut out = String::from("flowchart LR\n");
+    for (i, node) in nodes.iter().enumerate() {
+        let _ = writeln!(out, "    n{i}[\"{}\"]", mermaid_escape(node.title));
+    }
+    for &(a, b, _) in edges {
+        let _ = writeln!(out, "    n{a} --> n{b}");
+    }
+    out
+}
+
+/// Escape a quoted Mermaid label. `#` and `;` would start an entity code, so
+/// they become codes themselves; line breaks would end the node, so they
+/// become spaces.
+fn mermaid_escape(label: &str) -> String {
+    let mut out = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' => out.push_str("#quot;"),
+            '#' => out.push_str("#35;"),
+            ';' => out.push_str("#59;"),
+            '\n' => out.push(' '),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scanner;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn sample_notes() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../sample_notes")
+    }
+
+    /// Compare against a stored snapshot. Set `UPDATE_SNAPSHOTS=1` to rewrite them.
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/snapshots")
+            .join(name);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("missing snapshot {}", path.display()));
+        assert_eq!(actual, expected, "snapshot {name} differs");
+    }
+
+    #[test]
+    fn test_export_snapshots() -> Result<()> {
+        let root = sample_notes();
+        let graph = Scanner::new(&root).scan()?;
+        for (format, name) in [
+            (ExportFormat::Dot, "sample_notes.dot"),
+            (ExportFormat::GraphMl, "sample_notes.graphml"),
+            (ExportFormat::Json, "sample_notes.json"),
+            (ExportFormat::Mermaid, "sample_notes.mmd"),
+        ] {
+            assert_snapshot(name, &graph.export(format, &ExportScope::All, &root));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_mermaid_escaping() {
+        let mut graph = NoteGraph::new();
+        let root = Path::new("/vault");
+        let a = graph.add_note(root.join("a.md"), "C# tips; \"fast\" #quot;".into());
+        let b = graph.add_note(root.join("b.md"), "Two\r\nlines".into());
+        graph.add_link(a, b);
+        assert_snapshot(
+            "escaped_titles.mmd",
+            &graph.export(ExportFormat::Mermaid, &ExportScope::All, root),
+        );
+    }
+
+    #[test]
+    fn test_export_scopes() -> Result<()> {
+        let mut graph = NoteGraph::new();
+        let root = Path::new("/vault");
+        let a = graph.add_note(root.join("a.md"), "A \"quoted\"".into());
+        let b = graph.add_note(root.join("sub/b.md"), "B & co".into());
+        let c = graph.add_note(root.join("c.md"), "C".into());
+        graph.add_link_span(a, b, 0..5);
+        graph.add_link_span(a, b, 10..15);
+        graph.add_link(b, c);
+        graph.set_meta(
+            b,
+            crate::NoteMeta {
+                tags: vec!["topic".into()],
+                ..Default::default()
+            },
+        );
+
+        let near = ExportScope::Neighborhood {
+            center: a,
+            depth: 1,
+        };
+        assert_eq!(
+            graph.export(ExportFormat::Mermaid, &near, root),
+            "flowchart LR\n    n0[\"A #quot;quoted#quot;\"]\n    n1[\"B & co\"]\n    n0 --> n1\n"
+        );
+        let dot = graph.export(ExportFormat::Dot, &ExportScope::All, root);
+        assert!(dot.contains("\"a.md\" [label=\"A \\\"quoted\\\"\"];"));
+        assert!(dot.contains("\"sub/b.md\" -> \"c.md\";"));
+
+        let tagged = graph.export(
+            ExportFormat::GraphMl,
+            &ExportScope::Tag("#topic".into()),
+            root,
+        );
+        assert!(tagged.contains("<data key=\"title\">B &amp; co</data>"));
+        assert!(!tagged.contains("c.md"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&graph.export(ExportFormat::Json, &ExportScope::All, root))
+                .unwrap();
+        assert_eq!(json["edges"][0]["count"], 2);
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+
+        assert_eq!("GraphML".parse::<ExportFormat>()?, ExportFormat::GraphMl);
+        assert!("svg".parse::<ExportFormat>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+
+/// A frontmatter value: either a single scalar or a list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Value {
+    /// View the value as a list; scalars become a one-element list.
+    pub fn as_list(&self) -> Vec<String> {
+        match self {
+            Value::Text(text) if text.is_empty() => Vec::new(),
+            Value::Text(text) => vec![text.clone()],
+            Value::List(items) => items.clone(),
+        }
+    }
+}
+
+/// YAML-style frontmatter delimited by `---` lines at the top of a note.
+/// Only flat `key: value`, `key: [a, b]` and `key:` followed by `- item`
+/// lines are understood, which covers the usual aliases/tags/status fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frontmatter {
+    pub fields: BTreeMap<String, Value>,
+    /// Byte offset where the note body starts (after the closing `---`).
+    pub end: usize,
+}
+
+impl Frontmatter {
+    /// Parse the frontmatter block, if the content starts with one.
+    pub fn parse(content: &str) -> Option<Self> {
+        let rest = content
+            .strip_prefix("---\n")
+            .or_else(|| content.strip_prefix("---\r\n"))?;
+        let mut offset = content.len() - rest.len();
+        let mut fields = BTreeMap::new();
+        let mut list_key: Option<String> = None;
+
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            let line = line.trim_end();
+
+            if line == "---" || line == "..." {
+                return Some(Self {
+                    fields,
+                    end: offset,
+                });
+            }
+
+            if let Some(item) = line.trim_start().strip_prefix("- ")
+                && let Some(key) = &list_key
+            {
+                if let Some(Value::List(items)) = fields.get_mut(key) {
+                    items.push(unquote(item));
+                }
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_string();
+            let value = value.trim();
+
+            if value.is_empty() {
+                fields.insert(key.clone(), Value::List(Vec::new()));
+                list_key = Some(key);
+            } else if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                let items = inner
+                    .split(',')
+                    .map(unquote)
+                    .filter(|item| !item.is_empty())
+                    .collect();
+                fields.insert(key, Value::List(items));
+                list_key = None;
+            } else {
+                fields.insert(key, Value::Text(unquote(value)));
+                list_key = None;
+            }
+        }
+
+        // No closing delimiter: not frontmatter
+        None
+    }
+
+    /// Get a field by key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.fields.get(key)
+    }
+
+    /// Get a field as a list, empty if missing.
+    pub fn list(&self, key: &str) -> Vec<String> {
+        self.get(key).map(Value::as_list).unwrap_or_default()
+    }
+
+    /// Aliases declared under `aliases` (or the singular `alias`).
+    pub fn aliases(&self) -> Vec<String> {
+        let mut aliases = self.list("aliases");
+        aliases.extend(self.list("alias"));
+        aliases
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frontmatter() {
+        let content = "---\naliases: [Tenki, \"Weather\"]\nstatus: active\ntags:\n  - project\n  - rust\n---\n# Title\n";
+        let fm = Frontmatter::parse(content).unwrap();
+        assert_eq!(fm.aliases(), vec!["Tenki", "Weather"]);
+        assert_eq!(fm.get("status"), Some(&Value::Text("active".to_string())));
+        assert_eq!(fm.list("tags"), vec!["project", "rust"]);
+        assert_eq!(&content[fm.end..], "# Title\n");
+    }
+
+    #[test]
+    fn test_no_frontmatter() {
+        assert!(Frontmatter::parse("# Title\n---\n").is_none());
+        assert!(Frontmatter::parse("---\nunterminated: yes\n").is_none());
+    }
+}
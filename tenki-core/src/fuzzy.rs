@@ -0,0 +1,188 @@
+use crate::NoteGraph;
+use std::path::{Path, PathBuf};
+
+/// A successful fuzzy match of a query against a candidate string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i64,
+    /// Byte offsets of the matched characters in the candidate.
+    pub positions: Vec<usize>,
+}
+
+/// Match `query` as a case-insensitive subsequence of `candidate`.
+/// Consecutive runs, word starts and early matches score higher; gaps
+/// between matched characters cost a little.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let chars: Vec<(usize, char)> = candidate.char_indices().collect();
+
+    // Try every starting point of the first query char and keep the best
+    let mut best: Option<FuzzyMatch> = None;
+    for start in 0..chars.len() {
+        if !eq_ignore_case(chars[start].1, query[0]) {
+            continue;
+        }
+        if let Some(m) = match_from(&query, &chars, start)
+            && best.as_ref().is_none_or(|b| m.score > b.score)
+        {
+            best = Some(m);
+        }
+    }
+    best
+}
+
+fn match_from(query: &[char], chars: &[(usize, char)], start: usize) -> Option<FuzzyMatch> {
+    let mut score = 0i64;
+    let mut positions = Vec::with_capacity(query.len());
+    let mut qi = 0;
+    let mut prev: Option<usize> = None;
+
+    for (ci, &(offset, c)) in chars.iter().enumerate().skip(start) {
+        if qi == query.len() {
+            break;
+        }
+        if !eq_ignore_case(c, query[qi]) {
+            continue;
+        }
+
+        score += 10;
+        match prev {
+            Some(p) if p + 1 == ci => score += 15,
+            Some(p) => score -= (ci - p - 1).min(10) as i64,
+            None => score -= ci.min(15) as i64,
+        }
+        if ci == 0
+            || is_separator(chars[ci - 1].1)
+            || (chars[ci - 1].1.is_lowercase() && c.is_uppercase())
+        {
+            score += 12;
+        }
+
+        positions.push(offset);
+        prev = Some(ci);
+        qi += 1;
+    }
+
+    (qi == query.len()).then(|| FuzzyMatch {
+        // Prefer shorter candidates when everything else is equal
+        score: score - (chars.len() as i64 / 8),
+        positions,
+    })
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '/' | '\\' | '-' | '_' | '.' | '#')
+}
+
+/// Which part of a note a query matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchField {
+    Title,
+    Alias(String),
+    Path,
+}
+
+/// A note found by [`find_notes`].
+#[derive(Debug, Clone)]
+pub struct NoteMatch {
+    pub path: PathBuf,
+    pub title: String,
+    pub field: MatchField,
+    /// Byte offsets of matched characters in the matched field's text.
+    pub positions: Vec<usize>,
+    pub score: i64,
+}
+
+/// Fuzzy-find notes by title, alias or path relative to `root`, best first.
+pub fn find_notes(graph: &NoteGraph, root: &Path, query: &str) -> Vec<NoteMatch> {
+    let mut results: Vec<NoteMatch> = graph
+        .all_notes()
+        .into_iter()
+        .filter_map(|note| {
+            let relative = note.path.strip_prefix(root).unwrap_or(&note.path);
+            let mut candidates = vec![(MatchField::Title, note.title.as_str(), 0)];
+            candidates.extend(
+                note.aliases
+                    .iter()
+                    .map(|a| (MatchField::Alias(a.clone()), a.as_str(), 0)),
+            );
+            let relative = relative.to_string_lossy();
+            // Paths are long and noisy, so a title match wins a tie
+            candidates.push((MatchField::Path, relative.as_ref(), -5));
+
+            candidates
+                .into_iter()
+                .filter_map(|(field, text, bias)| {
+                    fuzzy_match(query, text).map(|m| (field, m.score + bias, m.positions))
+                })
+                .max_by_key(|(_, score, _)| *score)
+                .map(|(field, score, positions)| NoteMatch {
+                    path: note.path.clone(),
+                    title: note.title.clone(),
+                    field,
+                    positions,
+                    score,
+                })
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        let m = fuzzy_match("kbs", "Keyboard Shortcuts").unwrap();
+        assert_eq!(m.positions, vec![0, 3, 9]);
+        assert!(fuzzy_match("xyz", "Keyboard Shortcuts").is_none());
+
+        // Word starts and contiguous runs beat scattered matches
+        let tight = fuzzy_match("wiki", "Wikilinks").unwrap();
+        let loose = fuzzy_match("wiki", "Welcome to Tenki").unwrap_or(FuzzyMatch {
+            score: i64::MIN,
+            positions: Vec::new(),
+        });
+        assert!(tight.score > loose.score);
+    }
+
+    #[test]
+    fn test_find_notes() {
+        let mut graph = NoteGraph::new();
+        graph.add_note(
+            PathBuf::from("/v/welcome.md"),
+            "Welcome to Tenki".to_string(),
+        );
+        let idx = graph.add_note(
+            PathBuf::from("/v/misc/syntax.md"),
+            "Markdown Syntax".to_string(),
+        );
+        graph.add_alias(idx, "md".to_string());
+
+        let results = find_notes(&graph, Path::new("/v"), "md");
+        assert_eq!(results[0].title, "Markdown Syntax");
+        assert_eq!(results[0].field, MatchField::Alias("md".to_string()));
+
+        let results = find_notes(&graph, Path::new("/v"), "misc/");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].field, MatchField::Path);
+    }
+}
@@ -1,20 +1,73 @@
+use crate::frontmatter::Value;
+use crate::parser::Task;
+use crate::rank::Ranking;
+use crate::search::SearchIndex;
 use petgraph::graph::{DiGraph, NodeIndex};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use petgraph::visit::EdgeRef;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 /// Represents a note in the knowledge graph.
 #[derive(Debug, Clone)]
 pub struct Note {
     pub path: PathBuf,
     pub title: String,
+    pub aliases: Vec<String>,
+    pub meta: NoteMeta,
+}
+
+/// Metadata gathered from a note's frontmatter, body and file.
+#[derive(Debug, Clone, Default)]
+pub struct NoteMeta {
+    /// Frontmatter and inline tags, without the leading `#`.
+    pub tags: Vec<String>,
+    /// Frontmatter fields.
+    pub fields: BTreeMap<String, Value>,
+    pub modified: Option<SystemTime>,
+    pub created: Option<SystemTime>,
+    /// Number of words in the body, excluding frontmatter.
+    pub words: usize,
+    /// Checklist items in the note.
+    pub tasks: Vec<Task>,
+    /// Text of every heading, in order.
+    pub headings: Vec<String>,
+}
+
+impl Note {
+    /// Whether the note has a tag (case-insensitive, `#` optional).
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        self.meta.tags.iter().any(|t| t.to_lowercase() == tag)
+    }
+}
+
+/// A note linking to another, with the byte spans of each link in its source.
+#[derive(Debug, Clone)]
+pub struct Backlink<'a> {
+    pub note: &'a Note,
+    pub spans: &'a [Range<usize>],
+}
+
+/// A wikilink whose target matches no note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedLink {
+    pub target: String,
+    /// Byte span of the link in its source note.
+    pub span: Range<usize>,
 }
 
 /// A directed graph of notes and their links.
+/// Edges carry the byte spans of the link occurrences in the source note.
 #[derive(Debug, Default)]
 pub struct NoteGraph {
-    graph: DiGraph<Note, ()>,
+    graph: DiGraph<Note, Vec<Range<usize>>>,
     path_index: HashMap<PathBuf, NodeIndex>,
     title_index: HashMap<String, NodeIndex>,
+    search_index: SearchIndex,
+    ranking: Ranking,
+    unresolved: HashMap<NodeIndex, Vec<UnresolvedLink>>,
 }
 
 impl NoteGraph {
@@ -31,6 +84,8 @@ impl NoteGraph {
         let note = Note {
             path: path.clone(),
             title: title.clone(),
+            aliases: Vec::new(),
+            meta: NoteMeta::default(),
         };
         let idx = self.graph.add_node(note);
         self.path_index.insert(path, idx);
@@ -38,15 +93,176 @@ impl NoteGraph {
         idx
     }
 
+    /// Add an alternative title for a note. Links to the alias resolve to the
+    /// note unless another note already has that title.
+    pub fn add_alias(&mut self, idx: NodeIndex, alias: String) {
+        self.title_index.entry(alias.to_lowercase()).or_insert(idx);
+        if let Some(note) = self.graph.node_weight_mut(idx) {
+            note.aliases.push(alias);
+        }
+    }
+
+    /// Point a note at a new file, e.g. after it was moved. Its search index
+    /// entry is dropped until the note is re-read.
+    pub fn move_note(&mut self, idx: NodeIndex, path: PathBuf) {
+        let Some(note) = self.graph.node_weight_mut(idx) else {
+            return;
+        };
+        let old = std::mem::replace(&mut note.path, path.clone());
+        self.search_index.remove_document(&old);
+        self.path_index.remove(&old);
+        self.path_index.insert(path, idx);
+    }
+
+    /// Remove a note and its links. Each link to it from another note
+    /// becomes an unresolved link to its title; re-read those notes to
+    /// record the exact text they link with.
+    ///
+    /// The last note in the graph takes over the removed note's index, so
+    /// indices held from before are only valid for other notes. Call
+    /// [`update_ranking`](Self::update_ranking) once done changing the graph.
+    pub fn remove_note(&mut self, idx: NodeIndex) -> Option<Note> {
+        let last = NodeIndex::new(self.graph.node_count().checked_sub(1)?);
+        let inbound: Vec<(NodeIndex, Vec<Range<usize>>)> = self
+            .graph
+            .edges_directed(idx, petgraph::Direction::Incoming)
+            .filter(|edge| edge.source() != idx)
+            .map(|edge| (edge.source(), edge.weight().clone()))
+            .collect();
+        let note = self.graph.remove_node(idx)?;
+        self.path_index.remove(&note.path);
+        for name in std::iter::once(&note.title).chain(&note.aliases) {
+            let key = name.to_lowercase();
+            if self.title_index.get(&key) == Some(&idx) {
+                self.title_index.remove(&key);
+            }
+        }
+        self.unresolved.remove(&idx);
+        self.search_index.remove_document(&note.path);
+
+        if last != idx {
+            for i in self
+                .path_index
+                .values_mut()
+                .chain(self.title_index.values_mut())
+            {
+                if *i == last {
+                    *i = idx;
+                }
+            }
+            if let Some(links) = self.unresolved.remove(&last) {
+                self.unresolved.insert(idx, links);
+            }
+        }
+        for (source, spans) in inbound {
+            let source = if source == last { idx } else { source };
+            for span in spans {
+                self.add_unresolved(
+                    source,
+                    UnresolvedLink {
+                        target: note.title.clone(),
+                        span,
+                    },
+                );
+            }
+        }
+        Some(note)
+    }
+
+    /// Replace a note's metadata.
+    pub fn set_meta(&mut self, idx: NodeIndex, meta: NoteMeta) {
+        if let Some(note) = self.graph.node_weight_mut(idx) {
+            note.meta = meta;
+        }
+    }
+
+    /// Change a note's title and drop its aliases, ready for re-adding them.
+    pub fn update_note(&mut self, idx: NodeIndex, title: String) {
+        let Some(note) = self.graph.node_weight_mut(idx) else {
+            return;
+        };
+        let old_keys: Vec<String> = std::iter::once(&note.title)
+            .chain(&note.aliases)
+            .map(|t| t.to_lowercase())
+            .collect();
+        note.title = title.clone();
+        note.aliases.clear();
+
+        for key in old_keys {
+            if self.title_index.get(&key) == Some(&idx) {
+                self.title_index.remove(&key);
+            }
+        }
+        self.title_index.insert(title.to_lowercase(), idx);
+    }
+
+    /// Remove all outgoing links of a note, resolved or not.
+    pub fn clear_links(&mut self, idx: NodeIndex) {
+        self.unresolved.remove(&idx);
+        while let Some(edge) = self
+            .graph
+            .edges_directed(idx, petgraph::Direction::Outgoing)
+            .next()
+            .map(|e| e.id())
+        {
+            self.graph.remove_edge(edge);
+        }
+    }
+
     /// Add a link from one note to another.
     pub fn add_link(&mut self, from: NodeIndex, to: NodeIndex) {
         if !self.graph.contains_edge(from, to) {
-            self.graph.add_edge(from, to, ());
+            self.graph.add_edge(from, to, Vec::new());
+        }
+    }
+
+    /// Add a link from one note to another, recording where it occurs in the source.
+    pub fn add_link_span(&mut self, from: NodeIndex, to: NodeIndex, span: Range<usize>) {
+        match self.graph.find_edge(from, to) {
+            Some(edge) => self.graph[edge].push(span),
+            None => {
+                self.graph.add_edge(from, to, vec![span]);
+            }
+        }
+    }
+
+    /// Record a link from a note to a target that doesn't exist (yet).
+    pub fn add_unresolved(&mut self, from: NodeIndex, link: UnresolvedLink) {
+        self.unresolved.entry(from).or_default().push(link);
+    }
+
+    /// Links from a note that match no other note.
+    pub fn unresolved_links(&self, idx: NodeIndex) -> &[UnresolvedLink] {
+        self.unresolved.get(&idx).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every unresolved link in the vault with the note it is in.
+    pub fn all_unresolved(&self) -> impl Iterator<Item = (NodeIndex, &UnresolvedLink)> + '_ {
+        self.unresolved
+            .iter()
+            .flat_map(|(&idx, links)| links.iter().map(move |link| (idx, link)))
+    }
+
+    /// Retry every unresolved link against the current titles and aliases,
+    /// e.g. after a note is created. Returns how many links now resolve.
+    pub fn resolve_pending(&mut self) -> usize {
+        let mut resolved = 0;
+        for (from, links) in std::mem::take(&mut self.unresolved) {
+            for link in links {
+                match self.find_by_title(&link.target) {
+                    Some(to) => {
+                        self.add_link_span(from, to, link.span);
+                        resolved += 1;
+                    }
+                    None => self.add_unresolved(from, link),
+                }
+            }
         }
+        resolved
     }
 
     /// Find a note by its path.
-    pub fn find_by_path(&self, path: &PathBuf) -> Option<NodeIndex> {
+    pub fn find_by_path(&self, path: &Path) -> Option<NodeIndex> {
         self.path_index.get(path).copied()
     }
 
@@ -63,6 +279,17 @@ impl NoteGraph {
             .collect()
     }
 
+    /// Get all backlinks along with the spans of each link occurrence.
+    pub fn backlink_occurrences(&self, idx: NodeIndex) -> Vec<Backlink<'_>> {
+        self.graph
+            .edges_directed(idx, petgraph::Direction::Incoming)
+            .map(|edge| Backlink {
+                note: &self.graph[edge.source()],
+                spans: edge.weight(),
+            })
+            .collect()
+    }
+
     /// Get all forward links (notes this note links TO).
     pub fn forward_links(&self, idx: NodeIndex) -> Vec<&Note> {
         self.graph
@@ -71,11 +298,21 @@ impl NoteGraph {
             .collect()
     }
 
+    /// Whether `from` links to `to`.
+    pub fn has_link(&self, from: NodeIndex, to: NodeIndex) -> bool {
+        self.graph.contains_edge(from, to)
+    }
+
     /// Get a note by its index.
     pub fn get_note(&self, idx: NodeIndex) -> Option<&Note> {
         self.graph.node_weight(idx)
     }
 
+    /// Get the indices of all notes in the graph.
+    pub fn note_indices(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.graph.node_indices()
+    }
+
     /// Get all notes in the graph.
     pub fn all_notes(&self) -> Vec<&Note> {
         self.graph.node_weights().collect()
@@ -90,6 +327,32 @@ impl NoteGraph {
     pub fn link_count(&self) -> usize {
         self.graph.edge_count()
     }
+
+    /// Importance scores as of the last [`update_ranking`](Self::update_ranking).
+    pub fn ranking(&self) -> &Ranking {
+        &self.ranking
+    }
+
+    /// Recompute PageRank and betweenness after links changed. This walks the
+    /// whole graph, so call it once after a batch of changes.
+    pub fn update_ranking(&mut self) {
+        self.ranking = Ranking::compute(&self.graph);
+    }
+
+    /// The underlying petgraph graph, for the analysis modules.
+    pub(crate) fn inner(&self) -> &DiGraph<Note, Vec<Range<usize>>> {
+        &self.graph
+    }
+
+    /// Get the full-text index of note contents.
+    pub fn search_index(&self) -> &SearchIndex {
+        &self.search_index
+    }
+
+    /// Get the full-text index for incremental updates.
+    pub fn search_index_mut(&mut self) -> &mut SearchIndex {
+        &mut self.search_index
+    }
 }
 
 #[cfg(test)]
@@ -114,4 +377,95 @@ mod tests {
         assert_eq!(forward.len(), 1);
         assert_eq!(forward[0].title, "Note B");
     }
+
+    #[test]
+    fn test_remove_note() {
+        let mut graph = NoteGraph::new();
+        let a = graph.add_note(PathBuf::from("a.md"), "A".to_string());
+        let b = graph.add_note(PathBuf::from("b.md"), "B".to_string());
+        let c = graph.add_note(PathBuf::from("c.md"), "C".to_string());
+        graph.add_alias(c, "See".to_string());
+        graph.add_link(a, b);
+        graph.add_link(c, b);
+        graph.add_unresolved(
+            c,
+            UnresolvedLink {
+                target: "Missing".to_string(),
+                span: 0..11,
+            },
+        );
+
+        // C moves into A's slot
+        let removed = graph.remove_note(a).unwrap();
+        assert_eq!(removed.title, "A");
+        assert_eq!(graph.note_count(), 2);
+        assert_eq!(graph.find_by_title("A"), None);
+        assert_eq!(graph.find_by_path(Path::new("c.md")), Some(a));
+        assert_eq!(graph.find_by_title("see"), Some(a));
+        assert_eq!(graph.unresolved_links(a)[0].target, "Missing");
+        assert!(graph.has_link(a, b));
+        assert_eq!(graph.backlinks(b).len(), 1);
+    }
+
+    #[test]
+    fn test_remove_note_unresolves_links() {
+        let mut graph = NoteGraph::new();
+        let a = graph.add_note(PathBuf::from("a.md"), "A".to_string());
+        let b = graph.add_note(PathBuf::from("b.md"), "B".to_string());
+        let c = graph.add_note(PathBuf::from("c.md"), "C".to_string());
+        // Another note claimed B's alias first, so it must keep it
+        graph.add_alias(a, "Shared".to_string());
+        graph.add_alias(b, "Shared".to_string());
+        graph.add_link_span(a, b, 2..7);
+        graph.add_link_span(c, b, 0..5);
+        graph.add_link_span(c, b, 9..14);
+
+        graph.remove_note(b);
+        assert_eq!(graph.find_by_title("Shared"), Some(a));
+        assert_eq!(graph.link_count(), 0);
+        assert_eq!(graph.unresolved_links(a).len(), 1);
+        assert_eq!(graph.unresolved_links(a)[0].span, 2..7);
+        // C took B's index
+        let c = graph.find_by_path(Path::new("c.md")).unwrap();
+        assert_eq!(c, b);
+        let targets: Vec<&str> = graph
+            .unresolved_links(c)
+            .iter()
+            .map(|link| link.target.as_str())
+            .collect();
+        assert_eq!(targets, ["B", "B"]);
+
+        // A note with the title again picks the links up
+        let new_b = graph.add_note(PathBuf::from("new_b.md"), "B".to_string());
+        assert_eq!(graph.resolve_pending(), 3);
+        assert!(graph.has_link(a, new_b) && graph.has_link(c, new_b));
+    }
+
+    #[test]
+    fn test_backlink_occurrences() {
+        let mut graph = NoteGraph::new();
+
+        let note_a = graph.add_note(PathBuf::from("a.md"), "Note A".to_string());
+        let note_b = graph.add_note(PathBuf::from("b.md"), "Note B".to_string());
+
+        graph.add_link_span(note_a, note_b, 4..14);
+        graph.add_link_span(note_a, note_b, 20..30);
+
+        let backlinks = graph.backlink_occurrences(note_b);
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].note.title, "Note A");
+        assert_eq!(backlinks[0].spans, &[4..14, 20..30]);
+        assert_eq!(graph.link_count(), 1);
+    }
+
+    #[test]
+    fn test_alias_resolution() {
+        let mut graph = NoteGraph::new();
+
+        let note_a = graph.add_note(PathBuf::from("a.md"), "Note A".to_string());
+        graph.add_alias(note_a, "First".to_string());
+
+        assert_eq!(graph.find_by_title("first"), Some(note_a));
+        assert_eq!(graph.get_note(note_a).unwrap().aliases, vec!["First"]);
+    }
 }
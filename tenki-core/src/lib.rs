@@ -1,9 +1,51 @@
+pub mod analysis;
+pub mod calendar;
+pub mod complete;
+pub mod config;
+pub mod create;
 pub mod error;
+pub mod export;
+pub mod frontmatter;
+pub mod fuzzy;
 pub mod graph;
+pub mod mentions;
 pub mod parser;
+pub mod periodic;
+pub mod query;
+pub mod rank;
+pub mod related;
+pub mod rename;
 pub mod scanner;
+pub mod search;
+pub mod snippet;
+pub mod sort;
+pub mod template;
+pub mod trash;
 
+pub use analysis::{HealthReport, Subgraph};
+pub use calendar::DayActivity;
+pub use complete::{Completion, CompletionKind, LinkQuery};
+pub use config::{SavedSearch, VaultConfig};
+pub use create::slugify;
 pub use error::{Error, Result};
-pub use graph::NoteGraph;
-pub use parser::{parse_markdown, extract_wikilinks};
+pub use export::{ExportFormat, ExportScope};
+pub use frontmatter::Frontmatter;
+pub use fuzzy::{NoteMatch, find_notes, fuzzy_match};
+pub use graph::{Backlink, Note, NoteGraph, NoteMeta, UnresolvedLink};
+pub use mentions::{Mention, unlinked_mentions};
+pub use parser::{
+    Task, WikiLink, extract_tags, extract_tasks, extract_wikilink_spans, extract_wikilinks,
+    parse_markdown,
+};
+pub use periodic::{Period, PeriodicNote};
+pub use petgraph::graph::NodeIndex;
+pub use query::Query;
+pub use rank::Ranking;
+pub use related::Related;
+pub use rename::{FileEdit, LineChange, RenamePlan, TextEdit};
 pub use scanner::Scanner;
+pub use search::{SearchHit, SearchIndex, SearchQuery};
+pub use snippet::Snippet;
+pub use sort::{SortKey, SortOrder};
+pub use template::{Template, TemplateValues};
+pub use trash::{TrashEntry, Trashed};
@@ -0,0 +1,204 @@
+use crate::frontmatter::Frontmatter;
+use crate::parser::{code_ranges, extract_wikilink_spans};
+use crate::{Error, NoteGraph, Result, Snippet};
+use petgraph::graph::NodeIndex;
+use pulldown_cmark::{Event, Parser, Tag};
+use regex::RegexBuilder;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A plain-text mention of a note's title or alias that is not a link.
+#[derive(Debug, Clone)]
+pub struct Mention {
+    /// The note containing the mention.
+    pub path: PathBuf,
+    /// Byte range of the mention in that note.
+    pub span: Range<usize>,
+    /// The mentioned text as written.
+    pub text: String,
+    pub snippet: Snippet,
+}
+
+/// Find unlinked mentions of the note at `idx` across the whole graph.
+/// Matches the title and aliases case-insensitively on word boundaries,
+/// skipping code, frontmatter and existing links.
+pub fn unlinked_mentions(graph: &NoteGraph, idx: NodeIndex) -> Result<Vec<Mention>> {
+    let Some(target) = graph.get_note(idx) else {
+        return Ok(Vec::new());
+    };
+    let mut terms = vec![target.title.clone()];
+    terms.extend(target.aliases.iter().cloned());
+
+    let mut mentions = Vec::new();
+    for note in graph.all_notes() {
+        if note.path == target.path {
+            continue;
+        }
+        let content = fs::read_to_string(&note.path)?;
+        for span in find_mentions(&content, &terms) {
+            mentions.push(Mention {
+                path: note.path.clone(),
+                text: content[span.clone()].to_string(),
+                snippet: Snippet::at(&content, span.clone()),
+                span,
+            });
+        }
+    }
+
+    mentions.sort_by(|a, b| a.path.cmp(&b.path).then(a.span.start.cmp(&b.span.start)));
+    Ok(mentions)
+}
+
+/// Find unlinked occurrences of any of `terms` in `content`.
+pub fn find_mentions(content: &str, terms: &[String]) -> Vec<Range<usize>> {
+    let terms: Vec<&str> = terms
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    // Each term is matched on its own, so a longer term failing the word
+    // boundary check doesn't hide a shorter one at the same place
+    let mut found: Vec<Range<usize>> = Vec::new();
+    for term in terms {
+        let Ok(re) = RegexBuilder::new(&regex::escape(term))
+            .case_insensitive(true)
+            .build()
+        else {
+            continue;
+        };
+        found.extend(
+            re.find_iter(content)
+                .map(|m| m.range())
+                .filter(|r| is_word_boundary(content, r)),
+        );
+    }
+    // Prefer the longest term when several overlap
+    found.sort_by_key(|r| (r.start, std::cmp::Reverse(r.end)));
+    let mut end = 0;
+    found.retain(|r| {
+        let keep = r.start >= end;
+        if keep {
+            end = r.end;
+        }
+        keep
+    });
+
+    let mut excluded = code_ranges(content);
+    excluded.extend(extract_wikilink_spans(content).into_iter().map(|l| l.span));
+    excluded.extend(markdown_link_ranges(content));
+    if let Some(frontmatter) = Frontmatter::parse(content) {
+        excluded.push(0..frontmatter.end);
+    }
+
+    found
+        .into_iter()
+        .filter(|r| !excluded.iter().any(|e| e.start < r.end && r.start < e.end))
+        .collect()
+}
+
+/// Rewrite a mention into a wikilink to `title`, keeping the written text as
+/// display text when it differs from the title.
+pub fn link_mention(path: &Path, span: Range<usize>, expected: &str, title: &str) -> Result<()> {
+    let mut content = fs::read_to_string(path)?;
+    if content.get(span.clone()) != Some(expected) {
+        return Err(Error::StaleSpan(path.display().to_string()));
+    }
+
+    let link = if expected == title {
+        format!("[[{}]]", title)
+    } else {
+        format!("[[{}|{}]]", title, expected)
+    };
+    content.replace_range(span, &link);
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn markdown_link_ranges(content: &str) -> Vec<Range<usize>> {
+    Parser::new(content)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::Link { .. }) | Event::Start(Tag::Image { .. }) => Some(range),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_word_boundary(content: &str, range: &Range<usize>) -> bool {
+    let before = content[..range.start].chars().next_back();
+    let after = content[range.end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scanner;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_mentions() {
+        let content = "Tenki is nice. [[Tenki]] again, `tenki` in code, tenkis, and TENKI.";
+        let terms = vec!["Tenki".to_string()];
+        let found: Vec<&str> = find_mentions(content, &terms)
+            .into_iter()
+            .map(|r| &content[r])
+            .collect();
+        assert_eq!(found, vec!["Tenki", "TENKI"]);
+    }
+
+    #[test]
+    fn test_find_mentions_prefix_title() {
+        let content = "Tenki Notesy are not Tenki Notes, but tenki is.";
+        let terms = vec!["Tenki".to_string(), "Tenki Notes".to_string()];
+        let found: Vec<&str> = find_mentions(content, &terms)
+            .into_iter()
+            .map(|r| &content[r])
+            .collect();
+        assert_eq!(found, vec!["Tenki", "Tenki Notes", "tenki"]);
+    }
+
+    #[test]
+    fn test_unlinked_mentions_and_link() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(
+            root.join("target.md"),
+            "---\naliases: [Sky]\n---\n# Weather\n",
+        )?;
+        fs::write(
+            root.join("other.md"),
+            "# Other\n\nThe weather and the sky.\n",
+        )?;
+
+        let graph = Scanner::new(root).scan()?;
+        let idx = graph.find_by_title("Weather").unwrap();
+        let mentions = unlinked_mentions(&graph, idx)?;
+        assert_eq!(mentions.len(), 2);
+        assert_eq!(mentions[0].text, "weather");
+        assert_eq!(mentions[1].text, "sky");
+
+        // Rewrite from the back so earlier spans stay valid
+        for mention in mentions.iter().rev() {
+            link_mention(
+                &mention.path,
+                mention.span.clone(),
+                &mention.text,
+                "Weather",
+            )?;
+        }
+        let content = fs::read_to_string(root.join("other.md"))?;
+        assert!(content.contains("The [[Weather|weather]] and the [[Weather|sky]]."));
+
+        let graph = Scanner::new(root).scan()?;
+        let idx = graph.find_by_title("Weather").unwrap();
+        assert!(unlinked_mentions(&graph, idx)?.is_empty());
+        Ok(())
+    }
+}
@@ -1,9 +1,33 @@
+use chrono::NaiveDate;
 use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use regex::Regex;
+use std::ops::Range;
 use std::sync::LazyLock;
 
 static WIKILINK_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\[\[([^\]|]+)(?:\|[^\]]+)?\]\]").unwrap());
+    LazyLock::new(|| Regex::new(r"\[\[([^\]|]+)(?:\|([^\]]+))?\]\]").unwrap());
+
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s(,])#([\p{L}\p{N}_/-]+)").unwrap());
+
+static TASK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*[-*+] \[([ xX])\]\s+(.*)$").unwrap());
+
+static DUE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:📅|due:)\s*(\d{4}-\d{2}-\d{2})").unwrap());
+
+/// A single [[wikilink]] occurrence in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    /// Link target without any `#heading` fragment.
+    pub target: String,
+    /// Heading fragment after `#`, if any.
+    pub heading: Option<String>,
+    /// Display text after `|`, if any.
+    pub alias: Option<String>,
+    /// Byte range of the whole `[[...]]` in the source.
+    pub span: Range<usize>,
+}
 
 /// Extract all [[wikilinks]] from markdown content.
 /// Supports both [[link]] and [[link|display text]] syntax.
@@ -14,6 +38,95 @@ pub fn extract_wikilinks(content: &str) -> Vec<String> {
         .collect()
 }
 
+/// Extract all [[wikilinks]] from markdown content along with their byte spans.
+pub fn extract_wikilink_spans(content: &str) -> Vec<WikiLink> {
+    WIKILINK_RE
+        .captures_iter(content)
+        .map(|cap| {
+            let whole = cap.get(0).unwrap();
+            let (target, heading) = match cap[1].split_once('#') {
+                Some((target, heading)) => (target, Some(heading.trim().to_string())),
+                None => (&cap[1], None),
+            };
+            WikiLink {
+                target: target.trim().to_string(),
+                heading,
+                alias: cap.get(2).map(|m| m.as_str().to_string()),
+                span: whole.range(),
+            }
+        })
+        .collect()
+}
+
+/// Extract inline #tags from markdown content, skipping code and headings.
+/// Tags are returned without the leading `#`; purely numeric tags like `#1`
+/// are ignored.
+pub fn extract_tags(content: &str) -> Vec<String> {
+    let code = code_ranges(content);
+    let mut tags: Vec<String> = Vec::new();
+    for cap in TAG_RE.captures_iter(content) {
+        let m = cap.get(1).unwrap();
+        let tag = m.as_str().trim_end_matches(['/', '-']);
+        if tag.chars().all(|c| c.is_numeric())
+            || code.iter().any(|r| r.contains(&m.start()))
+            || tags.iter().any(|t| t.to_lowercase() == tag.to_lowercase())
+        {
+            continue;
+        }
+        tags.push(tag.to_string());
+    }
+    tags
+}
+
+/// A `- [ ]` checklist item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    /// 1-based line number.
+    pub line: usize,
+    pub text: String,
+    pub done: bool,
+    /// Date given as `due:2026-10-17` or `📅 2026-10-17` in the text.
+    pub due: Option<NaiveDate>,
+}
+
+/// Extract checklist items from markdown content, skipping code blocks.
+pub fn extract_tasks(content: &str) -> Vec<Task> {
+    let code = code_ranges(content);
+    let mut tasks = Vec::new();
+    let mut offset = 0;
+    for (i, line) in content.lines().enumerate() {
+        let start = offset;
+        offset += line.len() + 1;
+        let Some(cap) = TASK_RE.captures(line.trim_end_matches('\r')) else {
+            continue;
+        };
+        if code.iter().any(|r| r.contains(&start)) {
+            continue;
+        }
+        let text = cap[2].trim().to_string();
+        tasks.push(Task {
+            line: i + 1,
+            done: &cap[1] != " ",
+            due: DUE_RE
+                .captures(&text)
+                .and_then(|due| NaiveDate::parse_from_str(&due[1], "%Y-%m-%d").ok()),
+            text,
+        });
+    }
+    tasks
+}
+
+/// Byte ranges of inline code spans and code blocks in markdown content.
+pub fn code_ranges(content: &str) -> Vec<Range<usize>> {
+    Parser::new(content)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Code(_) | Event::Start(Tag::CodeBlock(_)) => Some(range),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Parsed representation of a markdown document.
 #[derive(Debug, Default)]
 pub struct ParsedNote {
@@ -45,10 +158,8 @@ pub fn parse_markdown(content: &str) -> ParsedNote {
                 }
                 note.headings.push(heading_text.clone());
             }
-            Event::Text(text) => {
-                if in_heading {
-                    heading_text.push_str(&text);
-                }
+            Event::Text(text) if in_heading => {
+                heading_text.push_str(&text);
             }
             Event::Start(Tag::Link { dest_url, .. }) => {
                 note.links.push(dest_url.to_string());
@@ -72,6 +183,50 @@ mod tests {
         assert_eq!(links, vec!["test", "multiple"]);
     }
 
+    #[test]
+    fn test_extract_wikilink_spans() {
+        let content = "See [[Note#Intro|the intro]] and [[Other]].";
+        let links = extract_wikilink_spans(content);
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].target, "Note");
+        assert_eq!(links[0].heading.as_deref(), Some("Intro"));
+        assert_eq!(links[0].alias.as_deref(), Some("the intro"));
+        assert_eq!(&content[links[0].span.clone()], "[[Note#Intro|the intro]]");
+        assert_eq!(&content[links[1].span.clone()], "[[Other]]");
+    }
+
+    #[test]
+    fn test_extract_tags() {
+        let content =
+            "# Heading\n\nTagged #project and #area/work, not#this or #123.\n\n`#code` #Project\n";
+        assert_eq!(extract_tags(content), vec!["project", "area/work"]);
+        assert_eq!(extract_tags("#Über and #über, #ÜBER"), vec!["Über"]);
+    }
+
+    #[test]
+    fn test_extract_tasks() {
+        let content = "# Tasks\n- [ ] Pay rent due:2026-10-20\n  * [x] Call Aiko 📅 2026-10-17\n\
+                       - [ ] Someday\n- [] not a task\n```\n- [ ] in code\n```\n";
+        let tasks = extract_tasks(content);
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].line, 2);
+        assert_eq!(tasks[0].text, "Pay rent due:2026-10-20");
+        assert!(!tasks[0].done);
+        assert_eq!(tasks[0].due, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert!(tasks[1].done);
+        assert_eq!(tasks[1].due, NaiveDate::from_ymd_opt(2026, 10, 17));
+        assert_eq!((tasks[2].line, tasks[2].due), (4, None));
+    }
+
+    #[test]
+    fn test_code_ranges() {
+        let content = "Use `[[inline]]` here.\n\n```\n[[fenced]]\n```\n";
+        let ranges = code_ranges(content);
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(&content[ranges[0].clone()], "`[[inline]]`");
+        assert!(content[ranges[1].clone()].contains("[[fenced]]"));
+    }
+
     #[test]
     fn test_parse_markdown() {
         let content = "# My Note\n\nSome text with [[wikilink]].\n\n## Section\n\nMore text.";
@@ -0,0 +1,248 @@
+use crate::graph::UnresolvedLink;
+use crate::{NoteGraph, Result, Scanner, Template, TemplateValues};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, TimeZone, Weekday};
+use petgraph::graph::NodeIndex;
+use std::path::{Path, PathBuf};
+
+/// Folder inside the vault holding daily, weekly and monthly notes.
+pub const JOURNAL_DIR: &str = "journal";
+
+/// How much time a periodic note covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    /// Name of the template in `templates/` new notes of this period start from.
+    pub fn template_name(self) -> &'static str {
+        match self {
+            Period::Day => "daily",
+            Period::Week => "weekly",
+            Period::Month => "monthly",
+        }
+    }
+}
+
+/// A note named after a date (`2026-10-17`), an ISO week (`2026-W42`) or a
+/// month (`2026-10`). Links to these count as resolved even before the note
+/// is written, since it has a known place in `journal/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeriodicNote {
+    pub period: Period,
+    /// First day of the period.
+    pub start: NaiveDate,
+}
+
+impl PeriodicNote {
+    /// The note of the given period containing `date`.
+    pub fn new(period: Period, date: NaiveDate) -> Self {
+        let start = match period {
+            Period::Day => date,
+            Period::Week => date - Days::new(date.weekday().num_days_from_monday().into()),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        };
+        Self { period, start }
+    }
+
+    /// The note of the given period containing today.
+    pub fn today(period: Period) -> Self {
+        Self::new(period, Local::now().date_naive())
+    }
+
+    /// Recognise a periodic note by its title.
+    pub fn parse(title: &str) -> Option<Self> {
+        let title = title.trim();
+        let note = if let Some((year, week)) = title.split_once("-W") {
+            let date =
+                NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)?;
+            Self::new(Period::Week, date)
+        } else if title.len() == 7 {
+            Self::new(
+                Period::Month,
+                NaiveDate::parse_from_str(&format!("{title}-01"), "%Y-%m-%d").ok()?,
+            )
+        } else {
+            Self::new(
+                Period::Day,
+                NaiveDate::parse_from_str(title, "%Y-%m-%d").ok()?,
+            )
+        };
+        // Only the canonical spelling, so `2026-1-5` stays an ordinary title
+        (note.title() == title).then_some(note)
+    }
+
+    pub fn title(&self) -> String {
+        let format = match self.period {
+            Period::Day => "%Y-%m-%d",
+            Period::Week => "%G-W%V",
+            Period::Month => "%Y-%m",
+        };
+        self.start.format(format).to_string()
+    }
+
+    /// Where the note lives, relative to the vault root.
+    pub fn relative_path(&self) -> PathBuf {
+        Path::new(JOURNAL_DIR).join(format!("{}.md", self.title()))
+    }
+
+    /// The note of the period after this one.
+    pub fn next(&self) -> Self {
+        let start = match self.period {
+            Period::Day => self.start + Days::new(1),
+            Period::Week => self.start + Days::new(7),
+            Period::Month => self.start + Months::new(1),
+        };
+        Self { start, ..*self }
+    }
+
+    /// The note of the period before this one.
+    pub fn prev(&self) -> Self {
+        let start = match self.period {
+            Period::Day => self.start - Days::new(1),
+            Period::Week => self.start - Days::new(7),
+            Period::Month => self.start - Months::new(1),
+        };
+        Self { start, ..*self }
+    }
+}
+
+impl UnresolvedLink {
+    /// The periodic note this link points to, if it names one. Such links
+    /// aren't broken; the note just hasn't been written yet.
+    pub fn periodic(&self) -> Option<PeriodicNote> {
+        PeriodicNote::parse(&self.target)
+    }
+}
+
+impl NoteGraph {
+    /// Unresolved links that don't name a periodic note either.
+    pub fn broken_links(&self) -> impl Iterator<Item = (NodeIndex, &UnresolvedLink)> + '_ {
+        self.all_unresolved()
+            .filter(|(_, link)| link.periodic().is_none())
+    }
+
+    /// The existing note for a period, wherever it is in the vault.
+    pub fn find_periodic(&self, note: PeriodicNote) -> Option<NodeIndex> {
+        self.find_by_title(&note.title())
+    }
+}
+
+impl Scanner {
+    /// Open the note for a period, writing it to `journal/` from its
+    /// template (`templates/daily.md` etc.) if it doesn't exist yet. Dates in
+    /// the template are those of the period, not of today.
+    pub fn periodic_note(&self, graph: &mut NoteGraph, note: PeriodicNote) -> Result<NodeIndex> {
+        if let Some(idx) = graph.find_periodic(note) {
+            return Ok(idx);
+        }
+        let title = note.title();
+        let content = match Template::find(self.root(), note.period.template_name())? {
+            Some(template) => {
+                let mut values = TemplateValues::new(&title);
+                let time = values.now.time();
+                if let Some(now) = Local
+                    .from_local_datetime(&note.start.and_time(time))
+                    .earliest()
+                {
+                    values.now = now;
+                }
+                template.render(&values)
+            }
+            None => String::new(),
+        };
+        let path = self.root().join(note.relative_path());
+        self.create_note_at(graph, &path, &title, &content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_periods() {
+        let day = PeriodicNote::parse("2026-10-17").unwrap();
+        assert_eq!(day, PeriodicNote::new(Period::Day, date(2026, 10, 17)));
+        assert_eq!(day.next().title(), "2026-10-18");
+        assert_eq!(day.prev().prev().title(), "2026-10-15");
+        assert_eq!(day.relative_path(), Path::new("journal/2026-10-17.md"));
+
+        let week = PeriodicNote::new(Period::Week, date(2026, 10, 17));
+        assert_eq!(week.start, date(2026, 10, 12));
+        assert_eq!(week.title(), "2026-W42");
+        assert_eq!(PeriodicNote::parse("2026-W42"), Some(week));
+        assert_eq!(
+            PeriodicNote::new(Period::Week, date(2027, 1, 1)).title(),
+            "2026-W53"
+        );
+        assert_eq!(week.next().title(), "2026-W43");
+
+        let month = PeriodicNote::parse("2026-12").unwrap();
+        assert_eq!(month.start, date(2026, 12, 1));
+        assert_eq!(month.next().title(), "2027-01");
+        assert_eq!(month.prev().title(), "2026-11");
+
+        for title in [
+            "2026-1-5",
+            "2026-02-30",
+            "2026-W60",
+            "2026-13",
+            "Meeting",
+            "2026-10-17x",
+        ] {
+            assert_eq!(PeriodicNote::parse(title), None, "{title}");
+        }
+    }
+
+    #[test]
+    fn test_periodic_note() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("templates"))?;
+        fs::write(
+            root.join("templates/daily.md"),
+            "# {{title}}\n\n{{date:%A}}\n",
+        )?;
+        fs::write(
+            root.join("a.md"),
+            "# A\n\n[[2026-10-17]] [[2026-W42]] [[Nowhere]]",
+        )?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        let a = graph.find_by_title("A").unwrap();
+        let broken: Vec<&str> = graph
+            .broken_links()
+            .map(|(_, link)| link.target.as_str())
+            .collect();
+        assert_eq!(broken, vec!["Nowhere"]);
+
+        let day = PeriodicNote::parse("2026-10-17").unwrap();
+        let idx = scanner.periodic_note(&mut graph, day)?;
+        assert_eq!(
+            fs::read_to_string(root.join("journal/2026-10-17.md"))?,
+            "# 2026-10-17\n\nSaturday\n"
+        );
+        assert!(graph.has_link(a, idx));
+        assert_eq!(scanner.periodic_note(&mut graph, day)?, idx);
+
+        // Without a template the note just gets its heading
+        let week = PeriodicNote::parse("2026-W42").unwrap();
+        let idx = scanner.periodic_note(&mut graph, week)?;
+        assert_eq!(
+            fs::read_to_string(root.join("journal/2026-W42.md"))?,
+            "# 2026-W42\n\n"
+        );
+        assert!(graph.has_link(a, idx));
+        Ok(())
+    }
+}
@@ -0,0 +1,515 @@
+use crate::frontmatter::Value;
+use crate::{Error, Note, NoteGraph, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use petgraph::graph::NodeIndex;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// A structured query over notes, their tags, links and frontmatter.
+///
+/// ```text
+/// tag:#project AND links-to:"Roadmap" AND status=active
+/// modified>2026-09-01 OR (backlinks>=3 -tag:archived)
+/// ```
+///
+/// Filters are `key:value` (`tag`, `links-to`, `linked-from`, `title`,
+/// `path`, `has`) or comparisons `key<op>value` with `=`, `!=`, `<`, `<=`,
+/// `>`, `>=` against frontmatter fields or the built-in `modified`,
+/// `created`, `backlinks` and `links`. Bare words and quoted phrases are
+/// full-text searches. Combine with `AND`, `OR`, `NOT`/`-` and parentheses;
+/// adjacent filters are ANDed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Filter(Filter),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Tag(String),
+    LinksTo(String),
+    LinkedFrom(String),
+    Title(String),
+    Path(String),
+    Has(String),
+    Text(String),
+    Compare { key: String, op: Op, value: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn test(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query string.
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = lex(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser
+            .parse_or()?
+            .ok_or_else(|| Error::InvalidQuery("empty query".into()))?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(Error::InvalidQuery(format!("unexpected {}", token)));
+        }
+        Ok(Self {
+            source: input.trim().to_string(),
+            expr,
+        })
+    }
+
+    /// The query text as written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate the query, returning matching notes ordered by title.
+    pub fn run(&self, graph: &NoteGraph) -> Vec<NodeIndex> {
+        let mut results: Vec<NodeIndex> = self.eval(&self.expr, graph).into_iter().collect();
+        results.sort_by_cached_key(|&idx| {
+            graph
+                .get_note(idx)
+                .map(|n| (n.title.to_lowercase(), n.path.clone()))
+        });
+        results
+    }
+
+    fn eval(&self, expr: &Expr, graph: &NoteGraph) -> HashSet<NodeIndex> {
+        match expr {
+            Expr::Filter(Filter::Text(text)) => {
+                // Reuse the full-text index rather than scanning content
+                let query = format!("\"{}\"", text.replace('"', ""));
+                graph
+                    .search_index()
+                    .search(&query)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|hit| graph.find_by_path(&hit.path))
+                    .collect()
+            }
+            Expr::Filter(filter) => graph
+                .note_indices()
+                .filter(|&idx| matches_filter(filter, graph, idx))
+                .collect(),
+            Expr::And(parts) => {
+                let mut parts = parts.iter().map(|p| self.eval(p, graph));
+                let first = parts.next().unwrap_or_default();
+                parts.fold(first, |acc, part| &acc & &part)
+            }
+            Expr::Or(parts) => parts.iter().flat_map(|p| self.eval(p, graph)).collect(),
+            Expr::Not(inner) => {
+                let excluded = self.eval(inner, graph);
+                graph
+                    .note_indices()
+                    .filter(|idx| !excluded.contains(idx))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn matches_filter(filter: &Filter, graph: &NoteGraph, idx: NodeIndex) -> bool {
+    let Some(note) = graph.get_note(idx) else {
+        return false;
+    };
+    match filter {
+        Filter::Tag(tag) => note.has_tag(tag),
+        Filter::LinksTo(title) => graph
+            .forward_links(idx)
+            .iter()
+            .any(|target| names_note(target, title)),
+        Filter::LinkedFrom(title) => graph
+            .backlinks(idx)
+            .iter()
+            .any(|source| names_note(source, title)),
+        Filter::Title(text) => contains_ignore_case(&note.title, text),
+        Filter::Path(text) => contains_ignore_case(&note.path.to_string_lossy(), text),
+        Filter::Has(key) => note.meta.fields.contains_key(key),
+        Filter::Text(_) => false,
+        Filter::Compare { key, op, value } => compare(graph, idx, note, key, *op, value),
+    }
+}
+
+fn compare(graph: &NoteGraph, idx: NodeIndex, note: &Note, key: &str, op: Op, value: &str) -> bool {
+    match key {
+        "modified" => compare_time(note.meta.modified, op, value),
+        "created" => compare_time(note.meta.created, op, value),
+        "backlinks" => compare_number(graph.backlinks(idx).len() as f64, op, value),
+        "links" => compare_number(graph.forward_links(idx).len() as f64, op, value),
+        _ => match note.meta.fields.get(key) {
+            // A list field compares true if any element does, except `!=`
+            // which must hold for all of them
+            Some(Value::List(items)) if op == Op::Ne => {
+                items.iter().all(|item| compare_values(item, op, value))
+            }
+            Some(Value::List(items)) => items.iter().any(|item| compare_values(item, op, value)),
+            Some(Value::Text(text)) => compare_values(text, op, value),
+            None => op == Op::Ne,
+        },
+    }
+}
+
+/// Compare as numbers, then as dates, then as case-insensitive text.
+fn compare_values(actual: &str, op: Op, expected: &str) -> bool {
+    if let (Ok(a), Ok(b)) = (actual.parse::<f64>(), expected.parse::<f64>()) {
+        return op.test(a.total_cmp(&b));
+    }
+    if let (Some(a), Some(b)) = (parse_date(actual), parse_date(expected)) {
+        return op.test(a.cmp(&b));
+    }
+    op.test(actual.to_lowercase().cmp(&expected.to_lowercase()))
+}
+
+fn compare_number(actual: f64, op: Op, expected: &str) -> bool {
+    expected
+        .parse::<f64>()
+        .is_ok_and(|b| op.test(actual.total_cmp(&b)))
+}
+
+fn compare_time(time: Option<SystemTime>, op: Op, expected: &str) -> bool {
+    let (Some(time), Some(expected)) = (time, parse_date(expected)) else {
+        return false;
+    };
+    let date = DateTime::<Local>::from(time).date_naive();
+    op.test(date.cmp(&expected))
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    match value {
+        "today" => Some(Local::now().date_naive()),
+        _ => NaiveDate::parse_from_str(value, "%Y-%m-%d").ok(),
+    }
+}
+
+/// Whether `name` is the note's title or an alias, compared the way links
+/// are resolved.
+fn names_note(note: &Note, name: &str) -> bool {
+    let name = name.to_lowercase();
+    std::iter::once(&note.title)
+        .chain(&note.aliases)
+        .any(|n| n.to_lowercase() == name)
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A filter or bare word; quotes are kept so values can contain operators.
+    Word(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::And => f.write_str("AND"),
+            Token::Or => f.write_str("OR"),
+            Token::Not => f.write_str("NOT"),
+            Token::Open => f.write_str("'('"),
+            Token::Close => f.write_str("')'"),
+        }
+    }
+}
+
+fn lex(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let mut word = String::new();
+                let mut quoted = false;
+                while let Some(&c) = chars.peek() {
+                    if !quoted && (c.is_whitespace() || matches!(c, '(' | ')')) {
+                        break;
+                    }
+                    if c == '"' {
+                        quoted = !quoted;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if quoted {
+                    return Err(Error::InvalidQuery("unterminated quote".into()));
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<Option<Expr>> {
+        let mut parts: Vec<Expr> = self.parse_and()?.into_iter().collect();
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            parts.extend(self.parse_and()?);
+        }
+        Ok(match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => Some(Expr::Or(parts)),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Option<Expr>> {
+        let mut parts = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::Or) | Some(Token::Close) => break,
+                Some(Token::And) if parts.is_empty() => {
+                    return Err(Error::InvalidQuery("AND needs a left-hand side".into()));
+                }
+                Some(Token::And) => self.pos += 1,
+                _ => parts.push(self.parse_unary()?),
+            }
+        }
+        Ok(match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => Some(Expr::And(parts)),
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| Error::InvalidQuery("unexpected end of query".into()))?;
+        self.pos += 1;
+        match token {
+            Token::Not => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Token::Open => {
+                let inner = self
+                    .parse_or()?
+                    .ok_or_else(|| Error::InvalidQuery("empty parentheses".into()))?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(Error::InvalidQuery("missing closing parenthesis".into()));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Token::Word(word) => parse_filter(&word).map(Expr::Filter),
+            Token::And | Token::Or | Token::Close => {
+                Err(Error::InvalidQuery(format!("unexpected {}", token)))
+            }
+        }
+    }
+}
+
+/// Parse one word into a filter: `key:value`, `key<op>value` or full text.
+fn parse_filter(word: &str) -> Result<Filter> {
+    // Find the first operator outside quotes
+    let mut quoted = false;
+    let mut split = None;
+    for (i, c) in word.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' | '=' | '!' | '<' | '>' if !quoted => {
+                split = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let Some(i) = split.filter(|&i| i > 0) else {
+        return Ok(Filter::Text(unquote(word)));
+    };
+    let key = unquote(&word[..i]).to_lowercase();
+    let rest = &word[i..];
+    let (op, value) = if let Some(value) = rest.strip_prefix(':') {
+        return Ok(match key.as_str() {
+            "tag" => Filter::Tag(unquote(value).trim_start_matches('#').to_string()),
+            "links-to" => Filter::LinksTo(unquote(value)),
+            "linked-from" => Filter::LinkedFrom(unquote(value)),
+            "title" => Filter::Title(unquote(value)),
+            "path" => Filter::Path(unquote(value)),
+            "has" => Filter::Has(unquote(value)),
+            // `status:active` reads naturally, so treat it as equality
+            _ => Filter::Compare {
+                key,
+                op: Op::Eq,
+                value: unquote(value),
+            },
+        });
+    } else if let Some(value) = rest.strip_prefix("!=") {
+        (Op::Ne, value)
+    } else if let Some(value) = rest.strip_prefix(">=") {
+        (Op::Ge, value)
+    } else if let Some(value) = rest.strip_prefix("<=") {
+        (Op::Le, value)
+    } else if let Some(value) = rest.strip_prefix('=') {
+        (Op::Eq, value)
+    } else if let Some(value) = rest.strip_prefix('>') {
+        (Op::Gt, value)
+    } else if let Some(value) = rest.strip_prefix('<') {
+        (Op::Lt, value)
+    } else {
+        return Err(Error::InvalidQuery(format!("bad filter '{}'", word)));
+    };
+
+    let value = unquote(value);
+    if value.is_empty() {
+        return Err(Error::InvalidQuery(format!("missing value in '{}'", word)));
+    }
+    Ok(Filter::Compare { key, op, value })
+}
+
+fn unquote(value: &str) -> String {
+    value.replace('"', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scanner;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn titles(graph: &NoteGraph, query: &str) -> Vec<String> {
+        Query::parse(query)
+            .unwrap()
+            .run(graph)
+            .into_iter()
+            .map(|idx| graph.get_note(idx).unwrap().title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Query::parse("").is_err());
+        assert!(Query::parse("(tag:a").is_err());
+        assert!(Query::parse("title:\"open").is_err());
+        assert!(Query::parse("status=").is_err());
+        assert!(Query::parse("AND tag:a").is_err());
+    }
+
+    #[test]
+    fn test_run_queries() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(root.join("roadmap.md"), "# Roadmap\n\nPlans.")?;
+        fs::write(
+            root.join("alpha.md"),
+            "---\nstatus: active\npriority: 2\ntags: [project]\n---\n# Alpha\n\nSee [[Roadmap]].",
+        )?;
+        fs::write(
+            root.join("beta.md"),
+            "---\nstatus: done\npriority: 10\n---\n# Beta\n\nA #project note about [[Roadmap]] plans.",
+        )?;
+        fs::write(root.join("gamma.md"), "# Gamma\n\nLinks to [[Alpha]].")?;
+        fs::write(
+            root.join("uber.md"),
+            "---\ntags: [überblick]\n---\n# Über\n\nA #Überblick of [[Gamma]].",
+        )?;
+
+        let graph = Scanner::new(root).scan()?;
+
+        assert_eq!(
+            titles(
+                &graph,
+                "tag:#project AND links-to:\"Roadmap\" AND status=active"
+            ),
+            vec!["Alpha"]
+        );
+        assert_eq!(titles(&graph, "tag:project -status:done"), vec!["Alpha"]);
+        assert_eq!(titles(&graph, "priority>5"), vec!["Beta"]);
+        assert_eq!(titles(&graph, "backlinks>=2"), vec!["Roadmap"]);
+        assert_eq!(
+            titles(&graph, "linked-from:Gamma OR title:bet"),
+            vec!["Alpha", "Beta"]
+        );
+        assert_eq!(titles(&graph, "plans has:status"), vec!["Beta"]);
+        assert_eq!(titles(&graph, "linked-from:über"), vec!["Gamma"]);
+        assert_eq!(titles(&graph, "tag:ÜBERBLICK"), vec!["Über"]);
+        let uber = graph.find_by_title("über").unwrap();
+        assert_eq!(graph.get_note(uber).unwrap().meta.tags, vec!["überblick"]);
+        assert_eq!(titles(&graph, "modified>2000-01-01").len(), 5);
+        assert!(titles(&graph, "modified<2000-01-01").is_empty());
+
+        Ok(())
+    }
+}
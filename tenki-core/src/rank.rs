@@ -0,0 +1,163 @@
+use crate::graph::Note;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-10;
+
+/// Importance scores for every note, recomputed after each scan.
+#[derive(Debug, Clone, Default)]
+pub struct Ranking {
+    pagerank: Vec<f64>,
+    betweenness: Vec<f64>,
+}
+
+impl Ranking {
+    pub(crate) fn compute(graph: &DiGraph<Note, Vec<Range<usize>>>) -> Self {
+        Self {
+            pagerank: pagerank(graph),
+            betweenness: betweenness(graph),
+        }
+    }
+
+    /// PageRank of a note; the scores of all notes sum to 1.
+    pub fn pagerank(&self, idx: NodeIndex) -> f64 {
+        self.pagerank.get(idx.index()).copied().unwrap_or_default()
+    }
+
+    /// Share of shortest link chains between other notes that pass through
+    /// this one, from 0 to 1.
+    pub fn betweenness(&self, idx: NodeIndex) -> f64 {
+        self.betweenness
+            .get(idx.index())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Outgoing links of each note, ignoring links to itself.
+fn out_links<N, E>(graph: &DiGraph<N, E>) -> Vec<Vec<usize>> {
+    graph
+        .node_indices()
+        .map(|idx| {
+            graph
+                .edges(idx)
+                .map(|e| e.target())
+                .filter(|&t| t != idx)
+                .map(NodeIndex::index)
+                .collect()
+        })
+        .collect()
+}
+
+/// PageRank by power iteration. Notes without outgoing links spread their
+/// rank evenly over all notes.
+fn pagerank<N, E>(graph: &DiGraph<N, E>) -> Vec<f64> {
+    let n = graph.node_count();
+    if n == 0 {
+        return Vec::new();
+    }
+    let links = out_links(graph);
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..MAX_ITERATIONS {
+        let dangling: f64 = (0..n)
+            .filter(|&i| links[i].is_empty())
+            .map(|i| rank[i])
+            .sum();
+        let base = (1.0 - DAMPING + DAMPING * dangling) / n as f64;
+        let mut next = vec![base; n];
+        for (source, targets) in links.iter().enumerate() {
+            let share = DAMPING * rank[source] / targets.len() as f64;
+            for &target in targets {
+                next[target] += share;
+            }
+        }
+        let delta: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if delta < TOLERANCE {
+            break;
+        }
+    }
+    rank
+}
+
+/// Betweenness centrality using Brandes' algorithm, normalised to 0..=1.
+fn betweenness<N, E>(graph: &DiGraph<N, E>) -> Vec<f64> {
+    let n = graph.node_count();
+    let links = out_links(graph);
+    let mut centrality = vec![0.0; n];
+    for source in 0..n {
+        let mut stack = Vec::with_capacity(n);
+        let mut preds = vec![Vec::new(); n];
+        let mut paths = vec![0.0; n];
+        let mut dist = vec![usize::MAX; n];
+        paths[source] = 1.0;
+        dist[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            for &w in &links[v] {
+                if dist[w] == usize::MAX {
+                    dist[w] = dist[v] + 1;
+                    queue.push_back(w);
+                }
+                if dist[w] == dist[v] + 1 {
+                    paths[w] += paths[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+        let mut dependency = vec![0.0; n];
+        while let Some(w) = stack.pop() {
+            for &v in &preds[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != source {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+    if n > 2 {
+        let pairs = ((n - 1) * (n - 2)) as f64;
+        for c in &mut centrality {
+            *c /= pairs;
+        }
+    }
+    centrality
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::NoteGraph;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_ranking() {
+        let mut graph = NoteGraph::new();
+        let mut add = |name: &str| graph.add_note(PathBuf::from(format!("{name}.md")), name.into());
+        let [a, b, c, d, e] = ["A", "B", "C", "D", "E"].map(&mut add);
+
+        // A -> B -> C, and D and E also link to C
+        graph.add_link(a, b);
+        graph.add_link(b, c);
+        graph.add_link(d, c);
+        graph.add_link(e, c);
+        graph.add_link(e, e);
+        graph.update_ranking();
+
+        let ranking = graph.ranking();
+        let total: f64 = [a, b, c, d, e].iter().map(|&i| ranking.pagerank(i)).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(ranking.pagerank(c) > ranking.pagerank(b));
+        assert!(ranking.pagerank(b) > ranking.pagerank(a));
+        assert!((ranking.pagerank(d) - ranking.pagerank(e)).abs() < 1e-12);
+
+        // Only A -> C goes through another note, out of 4 * 3 ordered pairs
+        assert!((ranking.betweenness(b) - 1.0 / 12.0).abs() < 1e-12);
+        assert_eq!(ranking.betweenness(c), 0.0);
+        assert_eq!(ranking.betweenness(a), 0.0);
+    }
+}
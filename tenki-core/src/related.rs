@@ -0,0 +1,165 @@
+use crate::NoteGraph;
+use petgraph::Direction;
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeSet, HashMap};
+
+const COCITATION_WEIGHT: f64 = 1.0;
+const COUPLING_WEIGHT: f64 = 1.0;
+const TAG_WEIGHT: f64 = 0.5;
+const UNRESOLVED_WEIGHT: f64 = 1.0;
+
+/// A note related to another, with what the two have in common.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Related {
+    pub note: NodeIndex,
+    pub score: f64,
+    /// Notes linking to both.
+    pub cocited_by: Vec<NodeIndex>,
+    /// Notes both link to.
+    pub shared_links: Vec<NodeIndex>,
+    /// Tags both have, lowercased.
+    pub shared_tags: Vec<String>,
+    /// Missing notes both link to, lowercased.
+    pub shared_unresolved: Vec<String>,
+}
+
+fn entry(related: &mut HashMap<NodeIndex, Related>, note: NodeIndex) -> &mut Related {
+    related.entry(note).or_insert_with(|| Related {
+        note,
+        ..Related::default()
+    })
+}
+
+impl NoteGraph {
+    /// Up to `limit` notes most related to `idx`, best first.
+    ///
+    /// Notes score for being linked from the same notes (co-citation),
+    /// linking to the same notes (bibliographic coupling), sharing tags and
+    /// linking to the same missing notes.
+    pub fn related(&self, idx: NodeIndex, limit: usize) -> Vec<Related> {
+        let Some(note) = self.get_note(idx) else {
+            return Vec::new();
+        };
+        let graph = self.inner();
+        let mut related: HashMap<NodeIndex, Related> = HashMap::new();
+
+        for citer in graph.neighbors_directed(idx, Direction::Incoming) {
+            for other in graph.neighbors_directed(citer, Direction::Outgoing) {
+                if other != idx && citer != other {
+                    entry(&mut related, other).cocited_by.push(citer);
+                }
+            }
+        }
+        for target in graph.neighbors_directed(idx, Direction::Outgoing) {
+            for other in graph.neighbors_directed(target, Direction::Incoming) {
+                if other != idx && target != other {
+                    entry(&mut related, other).shared_links.push(target);
+                }
+            }
+        }
+
+        let tags: BTreeSet<String> = note.meta.tags.iter().map(|t| t.to_lowercase()).collect();
+        if !tags.is_empty() {
+            for other in self.note_indices().filter(|&other| other != idx) {
+                let shared: Vec<String> = self.get_note(other).map_or(Vec::new(), |n| {
+                    n.meta
+                        .tags
+                        .iter()
+                        .map(|t| t.to_lowercase())
+                        .filter(|t| tags.contains(t))
+                        .collect()
+                });
+                if !shared.is_empty() {
+                    entry(&mut related, other).shared_tags = shared;
+                }
+            }
+        }
+
+        let missing: BTreeSet<String> = self
+            .unresolved_links(idx)
+            .iter()
+            .map(|link| link.target.to_lowercase())
+            .collect();
+        if !missing.is_empty() {
+            for (other, link) in self.all_unresolved() {
+                let target = link.target.to_lowercase();
+                if other != idx && missing.contains(&target) {
+                    let shared = &mut entry(&mut related, other).shared_unresolved;
+                    if !shared.contains(&target) {
+                        shared.push(target);
+                    }
+                }
+            }
+        }
+
+        let mut related: Vec<Related> = related
+            .into_values()
+            .map(|mut r| {
+                r.shared_unresolved.sort();
+                r.score = COCITATION_WEIGHT * r.cocited_by.len() as f64
+                    + COUPLING_WEIGHT * r.shared_links.len() as f64
+                    + TAG_WEIGHT * r.shared_tags.len() as f64
+                    + UNRESOLVED_WEIGHT * r.shared_unresolved.len() as f64;
+                r
+            })
+            .collect();
+        let title = |idx| self.get_note(idx).map(|n| n.title.to_lowercase());
+        related.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| title(a.note).cmp(&title(b.note)))
+        });
+        related.truncate(limit);
+        related
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NoteMeta, UnresolvedLink};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_related() {
+        let mut graph = NoteGraph::new();
+        let mut add = |name: &str| graph.add_note(PathBuf::from(format!("{name}.md")), name.into());
+        let [a, b, c, hub, target, lone] = ["A", "B", "C", "Hub", "Target", "Lone"].map(&mut add);
+
+        // Hub cites A and B; A and B both link to Target; A and C share a tag
+        // and a missing note
+        graph.add_link(hub, a);
+        graph.add_link(hub, b);
+        graph.add_link(a, target);
+        graph.add_link(b, target);
+        for idx in [a, c] {
+            graph.set_meta(
+                idx,
+                NoteMeta {
+                    tags: vec!["Idea".to_string()],
+                    ..NoteMeta::default()
+                },
+            );
+            graph.add_unresolved(
+                idx,
+                UnresolvedLink {
+                    target: "Someday".to_string(),
+                    span: 0..11,
+                },
+            );
+        }
+
+        let related = graph.related(a, 10);
+        let notes: Vec<_> = related.iter().map(|r| r.note).collect();
+        assert_eq!(notes, vec![b, c]);
+        assert_eq!(related[0].cocited_by, vec![hub]);
+        assert_eq!(related[0].shared_links, vec![target]);
+        assert_eq!(related[0].score, 2.0);
+        assert_eq!(related[1].shared_tags, vec!["idea"]);
+        assert_eq!(related[1].shared_unresolved, vec!["someday"]);
+        assert_eq!(related[1].score, 1.5);
+
+        assert_eq!(graph.related(a, 1).len(), 1);
+        assert!(graph.related(lone, 10).is_empty());
+    }
+}
@@ -0,0 +1,535 @@
+use crate::create::{check_folder, slugify};
+use crate::frontmatter::Frontmatter;
+use crate::parser::extract_wikilink_spans;
+use crate::{Error, NoteGraph, Result, Scanner};
+use petgraph::graph::NodeIndex;
+use pulldown_cmark::{Event, Parser, Tag};
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+/// A replacement of part of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Byte range in the file as it was when the plan was made.
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+/// A changed stretch of lines, for previewing a plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineChange {
+    /// 1-based number of the first line.
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// The edits a rename makes to one file.
+#[derive(Debug, Clone)]
+pub struct FileEdit {
+    pub path: PathBuf,
+    /// Contents when the plan was made; applying fails if the file changed since.
+    original: String,
+    /// Sorted and non-overlapping.
+    pub edits: Vec<TextEdit>,
+}
+
+impl FileEdit {
+    fn new(path: PathBuf, original: String, mut edits: Vec<TextEdit>) -> Self {
+        edits.sort_by_key(|edit| edit.span.start);
+        edits.dedup_by(|b, a| a.span == b.span);
+        Self {
+            path,
+            original,
+            edits,
+        }
+    }
+
+    /// The file's contents with the edits applied.
+    pub fn new_content(&self) -> String {
+        apply_edits(&self.original, &self.edits)
+    }
+
+    /// The lines the edits touch, before and after.
+    pub fn changed_lines(&self) -> Vec<LineChange> {
+        let content = &self.original;
+        let line_start = |i: usize| content[..i].rfind('\n').map_or(0, |p| p + 1);
+        let line_end = |i: usize| content[i..].find('\n').map_or(content.len(), |p| i + p);
+
+        // Group edits whose lines touch so each line shows up once
+        let mut groups: Vec<(Range<usize>, Vec<TextEdit>)> = Vec::new();
+        for edit in &self.edits {
+            let lines = line_start(edit.span.start)..line_end(edit.span.end);
+            match groups.last_mut() {
+                Some((range, edits)) if lines.start <= range.end => {
+                    range.end = range.end.max(lines.end);
+                    edits.push(edit.clone());
+                }
+                _ => groups.push((lines, vec![edit.clone()])),
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(range, edits)| {
+                let local: Vec<TextEdit> = edits
+                    .into_iter()
+                    .map(|edit| TextEdit {
+                        span: edit.span.start - range.start..edit.span.end - range.start,
+                        text: edit.text,
+                    })
+                    .collect();
+                let before = &content[range.clone()];
+                LineChange {
+                    line: content[..range.start].matches('\n').count() + 1,
+                    before: before.to_string(),
+                    after: apply_edits(before, &local),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Everything a rename or move will change, built by [`Scanner::plan_rename`]
+/// and carried out by [`Scanner::apply_rename`].
+#[derive(Debug, Clone)]
+pub struct RenamePlan {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub old_title: String,
+    pub new_title: String,
+    /// Files whose contents change, including the renamed note itself
+    /// (under its old path).
+    pub files: Vec<FileEdit>,
+}
+
+impl RenamePlan {
+    /// Whether applying the plan would change nothing.
+    pub fn is_empty(&self) -> bool {
+        self.from == self.to && self.files.is_empty()
+    }
+
+    /// Number of links rewritten in other notes.
+    pub fn link_count(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.path != self.from)
+            .map(|file| file.edits.len())
+            .sum()
+    }
+}
+
+fn apply_edits(content: &str, edits: &[TextEdit]) -> String {
+    let mut out = content.to_string();
+    for edit in edits.iter().rev() {
+        out.replace_range(edit.span.clone(), &edit.text);
+    }
+    out
+}
+
+impl Scanner {
+    /// Work out how to give the note at `idx` a new title and move it into
+    /// `folder` (relative to the root), without touching any files.
+    ///
+    /// The note's `# ` heading changes to the new title and its file is named
+    /// after it. Wikilinks to the old title are rewritten, keeping heading
+    /// fragments and display text; links through an alias are left alone.
+    /// Relative markdown links to the note, and from it if it changes folder,
+    /// are updated to the new path.
+    pub fn plan_rename(
+        &self,
+        graph: &NoteGraph,
+        idx: NodeIndex,
+        folder: &Path,
+        new_title: &str,
+    ) -> Result<RenamePlan> {
+        let note = graph
+            .get_note(idx)
+            .ok_or_else(|| Error::NoteNotFound(format!("{idx:?}")))?;
+        check_folder(folder)?;
+        let file_name = if new_title.trim() != note.title {
+            format!("{}.md", slugify(new_title))
+        } else {
+            note.path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let to = self.root().join(folder).join(file_name);
+        self.plan_move(graph, idx, &to, new_title)
+    }
+
+    /// Like [`Scanner::plan_rename`], but with the exact path to move the
+    /// note to, which must be inside the vault.
+    pub fn plan_move(
+        &self,
+        graph: &NoteGraph,
+        idx: NodeIndex,
+        to: &Path,
+        new_title: &str,
+    ) -> Result<RenamePlan> {
+        let note = graph
+            .get_note(idx)
+            .ok_or_else(|| Error::NoteNotFound(format!("{idx:?}")))?;
+        let new_title = new_title.trim();
+        if new_title.is_empty() {
+            return Err(Error::InvalidPath("empty title".to_string()));
+        }
+        let relative = to
+            .strip_prefix(self.root())
+            .map_err(|_| Error::InvalidPath(to.display().to_string()))?;
+        check_folder(relative)?;
+        let retitled = new_title != note.title;
+        if retitled
+            && graph
+                .find_by_title(new_title)
+                .is_some_and(|other| other != idx)
+        {
+            return Err(Error::NoteExists(new_title.to_string()));
+        }
+        let from = note.path.clone();
+        let to = to.to_path_buf();
+        if to != from && to.exists() {
+            return Err(Error::NoteExists(to.display().to_string()));
+        }
+
+        let mut contents: BTreeMap<PathBuf, String> = BTreeMap::new();
+        let mut edits: BTreeMap<PathBuf, Vec<TextEdit>> = BTreeMap::new();
+        let mut read = |path: &Path| -> Result<String> {
+            if let Some(content) = contents.get(path) {
+                return Ok(content.clone());
+            }
+            let content = fs::read_to_string(path)?;
+            contents.insert(path.to_path_buf(), content.clone());
+            Ok(content)
+        };
+
+        if retitled {
+            let content = read(&from)?;
+            edits
+                .entry(from.clone())
+                .or_default()
+                .push(heading_edit(&content, new_title));
+
+            for backlink in graph.backlink_occurrences(idx) {
+                let content = read(&backlink.note.path)?;
+                for span in backlink.spans {
+                    let Some(text) = content.get(span.clone()) else {
+                        continue;
+                    };
+                    let Some(link) = extract_wikilink_spans(text).into_iter().next() else {
+                        continue;
+                    };
+                    if link.target.to_lowercase() != note.title.to_lowercase() {
+                        continue;
+                    }
+                    let mut text = format!("[[{new_title}");
+                    if let Some(heading) = &link.heading {
+                        text.push('#');
+                        text.push_str(heading);
+                    }
+                    if let Some(alias) = &link.alias {
+                        text.push('|');
+                        text.push_str(alias);
+                    }
+                    text.push_str("]]");
+                    edits
+                        .entry(backlink.note.path.clone())
+                        .or_default()
+                        .push(TextEdit {
+                            span: span.clone(),
+                            text,
+                        });
+                }
+            }
+        }
+
+        if to != from {
+            for other in graph.all_notes() {
+                let content = read(&other.path)?;
+                let old_dir = other.path.parent().unwrap_or(Path::new(""));
+                let new_dir = if other.path == from {
+                    to.parent().unwrap_or(Path::new(""))
+                } else {
+                    old_dir
+                };
+                for (span, url) in relative_links(&content) {
+                    let (link_path, fragment) = match url.split_once('#') {
+                        Some((path, fragment)) => (path, Some(fragment)),
+                        None => (url.as_str(), None),
+                    };
+                    let target = normalize(&old_dir.join(percent_decode(link_path)));
+                    if target != from && new_dir == old_dir {
+                        continue;
+                    }
+                    let new_target = if target == from { &to } else { &target };
+                    let mut text = relative_path(new_dir, new_target);
+                    if link_path.contains("%20") {
+                        text = text.replace(' ', "%20");
+                    }
+                    if let Some(fragment) = fragment {
+                        text.push('#');
+                        text.push_str(fragment);
+                    }
+                    if text != url {
+                        edits
+                            .entry(other.path.clone())
+                            .or_default()
+                            .push(TextEdit { span, text });
+                    }
+                }
+            }
+        }
+
+        let files = edits
+            .into_iter()
+            .map(|(path, edits)| {
+                let original = contents.remove(&path).unwrap_or_default();
+                FileEdit::new(path, original, edits)
+            })
+            .collect();
+        Ok(RenamePlan {
+            from,
+            to,
+            old_title: note.title.clone(),
+            new_title: new_title.to_string(),
+            files,
+        })
+    }
+
+    /// Carry out a plan from [`Scanner::plan_rename`], then update the graph.
+    /// Nothing is written if any of the files changed since the plan was made.
+    pub fn apply_rename(&self, graph: &mut NoteGraph, plan: &RenamePlan) -> Result<NodeIndex> {
+        let idx = graph
+            .find_by_path(&plan.from)
+            .ok_or_else(|| Error::NoteNotFound(plan.from.display().to_string()))?;
+        for file in &plan.files {
+            if fs::read_to_string(&file.path)? != file.original {
+                return Err(Error::StaleSpan(file.path.display().to_string()));
+            }
+        }
+        for file in &plan.files {
+            fs::write(&file.path, file.new_content())?;
+        }
+        if plan.to != plan.from {
+            if let Some(parent) = plan.to.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&plan.from, &plan.to)?;
+            graph.move_note(idx, plan.to.clone());
+        }
+
+        self.update_file(graph, &plan.to)?;
+        for file in &plan.files {
+            if file.path != plan.from {
+                self.update_file(graph, &file.path)?;
+            }
+        }
+        graph.update_ranking();
+        Ok(idx)
+    }
+}
+
+/// Replace the note's `# ` heading with `title`, or add one after the
+/// frontmatter if it has none.
+fn heading_edit(content: &str, title: &str) -> TextEdit {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        // Same rule as the scanner uses to pick the title
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.trim_end().strip_prefix("# ") {
+            let start = offset + (line.len() - trimmed.len()) + 2;
+            return TextEdit {
+                span: start..start + rest.len(),
+                text: title.to_string(),
+            };
+        }
+        offset += line.len();
+    }
+    let start = Frontmatter::parse(content).map_or(0, |frontmatter| frontmatter.end);
+    TextEdit {
+        span: start..start,
+        text: format!("# {title}\n\n"),
+    }
+}
+
+/// Inline markdown links to local `.md` files, as the span of the URL and
+/// the URL itself.
+fn relative_links(content: &str) -> Vec<(Range<usize>, String)> {
+    Parser::new(content)
+        .into_offset_iter()
+        .filter_map(|(event, range)| {
+            let Event::Start(Tag::Link { dest_url, .. }) = event else {
+                return None;
+            };
+            let url = dest_url.to_string();
+            let path = url.split('#').next().unwrap_or_default();
+            if url.contains("://") || url.starts_with('/') || !path.ends_with(".md") {
+                return None;
+            }
+            // Reference-style links keep their URL elsewhere
+            let start = range.start + content[range].rfind(&url)?;
+            Some((start..start + url.len(), url))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Resolve `.` and `..` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// `target` relative to the directory `base`, with `/` separators.
+fn relative_path(base: &Path, target: &Path) -> String {
+    let base: Vec<_> = base.components().collect();
+    let target: Vec<_> = target.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    std::iter::repeat_n("..".to_string(), base.len() - common)
+        .chain(
+            target[common..]
+                .iter()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rename_and_move() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("sub"))?;
+        fs::write(
+            root.join("a.md"),
+            "# Alpha\n\nSee [[Beta]] and [[beta#Intro|the intro]].\nAlso [b](sub/beta.md#intro).\n",
+        )?;
+        fs::write(
+            root.join("sub/beta.md"),
+            "---\naliases: [B]\n---\n# Beta\n\n## Intro\nBack to [a](../a.md) and [[B]].\n",
+        )?;
+
+        let a_original = fs::read_to_string(root.join("a.md"))?;
+        let beta_original = fs::read_to_string(root.join("sub/beta.md"))?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        let beta = graph.find_by_title("Beta").unwrap();
+        let plan = scanner.plan_rename(&graph, beta, Path::new(""), "Gamma")?;
+        assert_eq!(plan.to, root.join("gamma.md"));
+        assert_eq!(plan.link_count(), 3);
+
+        let a_edit = plan
+            .files
+            .iter()
+            .find(|f| f.path == root.join("a.md"))
+            .unwrap();
+        assert_eq!(
+            a_edit.changed_lines(),
+            vec![
+                LineChange {
+                    line: 3,
+                    before: "See [[Beta]] and [[beta#Intro|the intro]].".into(),
+                    after: "See [[Gamma]] and [[Gamma#Intro|the intro]].".into(),
+                },
+                LineChange {
+                    line: 4,
+                    before: "Also [b](sub/beta.md#intro).".into(),
+                    after: "Also [b](gamma.md#intro).".into(),
+                },
+            ]
+        );
+        // Nothing is written until the plan is applied
+        assert!(root.join("sub/beta.md").exists());
+
+        let idx = scanner.apply_rename(&mut graph, &plan)?;
+        assert_eq!(idx, beta);
+        assert!(!root.join("sub/beta.md").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("gamma.md"))?,
+            "---\naliases: [B]\n---\n# Gamma\n\n## Intro\nBack to [a](a.md) and [[B]].\n"
+        );
+        let alpha = graph.find_by_title("Alpha").unwrap();
+        assert_eq!(graph.find_by_title("Gamma"), Some(beta));
+        assert_eq!(graph.find_by_title("Beta"), None);
+        assert!(graph.has_link(alpha, beta));
+        assert_eq!(graph.all_unresolved().count(), 0);
+
+        // Moving it back restores every file, with links in the title's case
+        let back = scanner.plan_move(&graph, beta, &root.join("sub/beta.md"), "Beta")?;
+        scanner.apply_rename(&mut graph, &back)?;
+        assert_eq!(
+            fs::read_to_string(root.join("a.md"))?,
+            a_original.replace("[[beta#", "[[Beta#")
+        );
+        assert_eq!(fs::read_to_string(root.join("sub/beta.md"))?, beta_original);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_checks() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("one.md"), "[[Two]]\n")?;
+        fs::write(root.join("two.md"), "# Two\n")?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        let one = graph.find_by_title("one").unwrap();
+        assert!(matches!(
+            scanner.plan_rename(&graph, one, Path::new(""), "two"),
+            Err(Error::NoteExists(_))
+        ));
+        assert!(
+            scanner
+                .plan_rename(&graph, one, Path::new(""), "one")?
+                .is_empty()
+        );
+
+        // A note titled by its file name gains a heading
+        let plan = scanner.plan_rename(&graph, one, Path::new(""), "First")?;
+        assert_eq!(plan.files[0].new_content(), "# First\n\n[[Two]]\n");
+
+        fs::write(root.join("one.md"), "changed")?;
+        assert!(matches!(
+            scanner.apply_rename(&mut graph, &plan),
+            Err(Error::StaleSpan(_))
+        ));
+        Ok(())
+    }
+}
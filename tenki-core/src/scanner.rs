@@ -1,4 +1,12 @@
-use crate::{extract_wikilinks, NoteGraph, Result};
+use crate::config::TENKI_DIR;
+use crate::frontmatter::Frontmatter;
+use crate::graph::{NoteMeta, UnresolvedLink};
+use crate::parser::{
+    WikiLink, extract_tags, extract_tasks, extract_wikilink_spans, parse_markdown,
+};
+use crate::template::TEMPLATES_DIR;
+use crate::{Error, NoteGraph, Result};
+use petgraph::graph::NodeIndex;
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
@@ -23,21 +31,14 @@ impl Scanner {
     /// Scan the directory and build a graph of notes.
     pub fn scan(&self) -> Result<NoteGraph> {
         let mut graph = NoteGraph::new();
-        let mut pending_links: Vec<(PathBuf, Vec<String>)> = Vec::new();
+        let mut pending_links: Vec<(PathBuf, Vec<WikiLink>)> = Vec::new();
 
         // First pass: collect all notes
-        for entry in WalkDir::new(&self.root)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
+        for entry in self.walk() {
             let path = entry.path();
             if path.extension().is_some_and(|ext| ext == "md") {
                 let content = fs::read_to_string(path)?;
-                let title = self.extract_title(path, &content);
-                let wikilinks = extract_wikilinks(&content);
-
-                graph.add_note(path.to_path_buf(), title);
+                let (_, wikilinks) = self.add_file(&mut graph, path, &content);
                 if !wikilinks.is_empty() {
                     pending_links.push((path.to_path_buf(), wikilinks));
                 }
@@ -47,28 +48,150 @@ impl Scanner {
         // Second pass: resolve links
         for (source_path, wikilinks) in pending_links {
             if let Some(source_idx) = graph.find_by_path(&source_path) {
-                for link_target in wikilinks {
-                    // Try to find target by title
-                    if let Some(target_idx) = graph.find_by_title(&link_target) {
-                        graph.add_link(source_idx, target_idx);
-                    }
-                    // TODO: Also try to match by filename without extension
-                }
+                Self::resolve_links(&mut graph, source_idx, wikilinks);
             }
         }
 
+        graph.update_ranking();
         Ok(graph)
     }
 
+    /// Re-read a single note and update its title, aliases, outgoing links and
+    /// search index entry in place. Adds the note if it is new.
+    ///
+    /// If the note's title or aliases changed, links to it from other notes
+    /// are resolved again, and unresolved links that now match it are added.
+    /// The ranking is left alone: call [`NoteGraph::update_ranking`] once
+    /// after a batch of updates.
+    pub fn update_file(&self, graph: &mut NoteGraph, path: &Path) -> Result<NodeIndex> {
+        let content = fs::read_to_string(path)?;
+        let old_names = graph.find_by_path(path).map(|idx| note_names(graph, idx));
+        let (idx, wikilinks) = self.add_file(graph, path, &content);
+        graph.clear_links(idx);
+        Self::resolve_links(graph, idx, wikilinks);
+
+        if old_names.is_some_and(|names| names != note_names(graph, idx)) {
+            // Links that named the old title may point elsewhere now
+            let linking: Vec<NodeIndex> = graph
+                .backlinks(idx)
+                .into_iter()
+                .filter_map(|note| graph.find_by_path(&note.path))
+                .filter(|&source| source != idx)
+                .collect();
+            for source in linking {
+                self.relink(graph, source)?;
+            }
+        }
+        graph.resolve_pending();
+        Ok(idx)
+    }
+
+    /// Re-read a note's links without touching anything else about it.
+    fn relink(&self, graph: &mut NoteGraph, idx: NodeIndex) -> Result<()> {
+        let Some(note) = graph.get_note(idx) else {
+            return Ok(());
+        };
+        let content = fs::read_to_string(&note.path)?;
+        graph.clear_links(idx);
+        Self::resolve_links(graph, idx, extract_wikilink_spans(&content));
+        Ok(())
+    }
+
+    /// Write `content` to a note edited in place and re-index it. `original`
+    /// is what the file held when editing started; if it changed on disk
+    /// since, nothing is written and [`Error::EditConflict`] is returned.
+    pub fn save_note(
+        &self,
+        graph: &mut NoteGraph,
+        path: &Path,
+        original: &str,
+        content: &str,
+    ) -> Result<NodeIndex> {
+        let current = match fs::read_to_string(path) {
+            Ok(current) => current,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        if current != original {
+            return Err(Error::EditConflict(path.display().to_string()));
+        }
+        fs::write(path, content)?;
+        let idx = self.update_file(graph, path)?;
+        graph.update_ranking();
+        Ok(idx)
+    }
+
+    /// Add or refresh a note's node and index entry, returning its links for resolving.
+    fn add_file(
+        &self,
+        graph: &mut NoteGraph,
+        path: &Path,
+        content: &str,
+    ) -> (NodeIndex, Vec<WikiLink>) {
+        let title = self.extract_title(path, content);
+        let idx = match graph.find_by_path(path) {
+            Some(idx) => {
+                graph.update_note(idx, title);
+                idx
+            }
+            None => graph.add_note(path.to_path_buf(), title),
+        };
+
+        let mut meta = NoteMeta::default();
+        let mut body = content;
+        if let Some(frontmatter) = Frontmatter::parse(content) {
+            body = &content[frontmatter.end..];
+            for alias in frontmatter.aliases() {
+                graph.add_alias(idx, alias);
+            }
+            meta.tags = frontmatter
+                .list("tags")
+                .iter()
+                .map(|t| t.trim_start_matches('#').to_string())
+                .collect();
+            meta.fields = frontmatter.fields;
+        }
+        for tag in extract_tags(content) {
+            let key = tag.to_lowercase();
+            if !meta.tags.iter().any(|t| t.to_lowercase() == key) {
+                meta.tags.push(tag);
+            }
+        }
+        meta.words = body.split_whitespace().count();
+        meta.tasks = extract_tasks(content);
+        meta.headings = parse_markdown(body).headings;
+        if let Ok(metadata) = fs::metadata(path) {
+            meta.modified = metadata.modified().ok();
+            meta.created = metadata.created().ok();
+        }
+        graph.set_meta(idx, meta);
+
+        graph.search_index_mut().update_document(path, content);
+        (idx, extract_wikilink_spans(content))
+    }
+
+    fn resolve_links(graph: &mut NoteGraph, source_idx: NodeIndex, wikilinks: Vec<WikiLink>) {
+        for link in wikilinks {
+            // Try to find target by title
+            match graph.find_by_title(&link.target) {
+                Some(target_idx) => graph.add_link_span(source_idx, target_idx, link.span),
+                None => graph.add_unresolved(
+                    source_idx,
+                    UnresolvedLink {
+                        target: link.target,
+                        span: link.span,
+                    },
+                ),
+            }
+            // TODO: Also try to match by filename without extension
+        }
+    }
+
     /// List all markdown files in the directory.
     pub fn list_files(&self) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
 
-        for entry in WalkDir::new(&self.root)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
+        for entry in self.walk() {
             let path = entry.path();
             if path.extension().is_some_and(|ext| ext == "md") {
                 files.push(path.to_path_buf());
@@ -79,6 +202,17 @@ impl Scanner {
         Ok(files)
     }
 
+    /// Every entry below the root, skipping tenki's own directory and the
+    /// templates folder.
+    fn walk(&self) -> impl Iterator<Item = walkdir::DirEntry> + '_ {
+        let skipped = [self.root.join(TENKI_DIR), self.root.join(TEMPLATES_DIR)];
+        WalkDir::new(&self.root)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(move |e| !skipped.iter().any(|dir| e.path() == dir))
+            .filter_map(|e| e.ok())
+    }
+
     /// Extract title from content or fall back to filename.
     fn extract_title(&self, path: &Path, content: &str) -> String {
         // Try to extract first H1 heading
@@ -97,6 +231,18 @@ impl Scanner {
     }
 }
 
+/// A note's title and aliases, lowercased as links match them.
+fn note_names(graph: &NoteGraph, idx: NodeIndex) -> Vec<String> {
+    let mut names: Vec<String> = graph
+        .get_note(idx)
+        .into_iter()
+        .flat_map(|note| std::iter::once(&note.title).chain(&note.aliases))
+        .map(|name| name.to_lowercase())
+        .collect();
+    names.sort();
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,7 +262,132 @@ mod tests {
 
         assert_eq!(graph.note_count(), 2);
         assert_eq!(graph.link_count(), 1);
+        assert_eq!(graph.search_index().search("content")?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scanner_skips_dot_tenki() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(root.join("note.md"), "# Note\n\nSee [[Hidden]].")?;
+        fs::create_dir_all(root.join(TENKI_DIR))?;
+        fs::write(root.join(TENKI_DIR).join("hidden.md"), "# Hidden")?;
+
+        let scanner = Scanner::new(root);
+        let graph = scanner.scan()?;
+        assert_eq!(scanner.list_files()?, vec![root.join("note.md")]);
+        assert_eq!(graph.note_count(), 1);
+        assert_eq!(graph.find_by_title("Hidden"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scanner_unresolved_links() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(
+            root.join("note_a.md"),
+            "# Note A\n\nLinks to [[Note B]] and [[Missing]].",
+        )?;
+        fs::write(root.join("note_b.md"), "# Note B\n\nSee [[missing]] too.")?;
+
+        let graph = Scanner::new(root).scan()?;
+        assert_eq!(graph.link_count(), 1);
+
+        let note_a = graph.find_by_title("Note A").unwrap();
+        let unresolved = graph.unresolved_links(note_a);
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].target, "Missing");
+        assert_eq!(graph.all_unresolved().count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_file() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(root.join("note_a.md"), "# Note A\n\nLinks to [[Note B]].")?;
+        fs::write(root.join("note_b.md"), "# Note B\n\nSome content.")?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+
+        fs::write(root.join("note_a.md"), "# Renamed\n\nNo links any more.")?;
+        let idx = scanner.update_file(&mut graph, &root.join("note_a.md"))?;
+
+        assert_eq!(graph.get_note(idx).unwrap().title, "Renamed");
+        assert_eq!(graph.find_by_title("Note A"), None);
+        assert_eq!(graph.link_count(), 0);
+        assert_eq!(graph.search_index().search("links")?.len(), 1);
+        assert!(graph.search_index().search("Note B")?.len() == 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_file_retitle() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        fs::write(root.join("note_a.md"), "# Note A\n\nSee [[Note B]].")?;
+        fs::write(root.join("note_b.md"), "# Note B\n\nSome content.")?;
+        fs::write(root.join("note_c.md"), "# Note C\n\nAbout [[Renamed]].")?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        let a = graph.find_by_title("Note A").unwrap();
+        let c = graph.find_by_title("Note C").unwrap();
+
+        fs::write(root.join("note_b.md"), "# Renamed\n\nSome content.")?;
+        let b = scanner.update_file(&mut graph, &root.join("note_b.md"))?;
+
+        // The old title no longer matches, the new one does
+        assert!(!graph.has_link(a, b));
+        assert_eq!(graph.unresolved_links(a)[0].target, "Note B");
+        assert!(graph.has_link(c, b));
+        assert!(graph.unresolved_links(c).is_empty());
+
+        fs::write(
+            root.join("note_b.md"),
+            "---\naliases: [Note B]\n---\n# Renamed\n",
+        )?;
+        scanner.update_file(&mut graph, &root.join("note_b.md"))?;
+        assert!(graph.has_link(a, b));
+        assert_eq!(graph.all_unresolved().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_note() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let path = root.join("note_a.md");
+        fs::write(&path, "# Note A\n")?;
+        fs::write(root.join("note_b.md"), "# Note B\n\nSee [[Note C]].")?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        let b = graph.find_by_title("Note B").unwrap();
+
+        let idx = scanner.save_note(&mut graph, &path, "# Note A\n", "# Note C\n\n[[Note B]]")?;
+        assert_eq!(fs::read_to_string(&path)?, "# Note C\n\n[[Note B]]");
+        assert!(graph.has_link(idx, b));
+        assert!(graph.has_link(b, idx));
 
+        // Someone else changed the file in the meantime
+        assert!(matches!(
+            scanner.save_note(&mut graph, &path, "# Note A\n", "# Lost\n"),
+            Err(Error::EditConflict(_))
+        ));
+        assert_eq!(fs::read_to_string(&path)?, "# Note C\n\n[[Note B]]");
         Ok(())
     }
 }
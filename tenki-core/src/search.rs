@@ -0,0 +1,587 @@
+use crate::{Error, Result, Snippet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// BM25 term frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 document length normalisation.
+const B: f64 = 0.75;
+
+type DocId = usize;
+
+/// A token occurrence: its position in the token stream and byte range in the text.
+#[derive(Debug, Clone)]
+struct Posting {
+    position: usize,
+    span: Range<usize>,
+}
+
+#[derive(Debug)]
+struct Document {
+    path: PathBuf,
+    content: String,
+    /// Number of tokens, for length normalisation.
+    length: usize,
+    /// Distinct terms, so removing the document only touches their postings.
+    terms: Vec<String>,
+}
+
+/// An inverted index over note contents supporting ranked full-text search.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    /// Removed documents leave a `None` so ids stay stable, until the slot
+    /// is reused by the next added document.
+    docs: Vec<Option<Document>>,
+    free: Vec<DocId>,
+    doc_ids: HashMap<PathBuf, DocId>,
+    /// Term -> document -> occurrences. Kept sorted for prefix lookups.
+    postings: BTreeMap<String, HashMap<DocId, Vec<Posting>>>,
+    total_length: usize,
+}
+
+/// A note matching a search, with its matching lines.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub path: PathBuf,
+    pub score: f64,
+    /// One snippet per matching line, in document order.
+    pub lines: Vec<Snippet>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the indexed content of a note.
+    pub fn update_document(&mut self, path: &Path, content: &str) {
+        self.remove_document(path);
+
+        let id = self.free.pop().unwrap_or(self.docs.len());
+        let mut length = 0;
+        let mut terms = Vec::new();
+        for (position, (term, span)) in tokenize(content).enumerate() {
+            let occurrences = self
+                .postings
+                .entry(term.clone())
+                .or_default()
+                .entry(id)
+                .or_default();
+            if occurrences.is_empty() {
+                terms.push(term);
+            }
+            occurrences.push(Posting { position, span });
+            length += 1;
+        }
+
+        self.total_length += length;
+        let doc = Document {
+            path: path.to_path_buf(),
+            content: content.to_string(),
+            length,
+            terms,
+        };
+        match self.docs.get_mut(id) {
+            Some(slot) => *slot = Some(doc),
+            None => self.docs.push(Some(doc)),
+        }
+        self.doc_ids.insert(path.to_path_buf(), id);
+    }
+
+    /// Remove a note from the index.
+    pub fn remove_document(&mut self, path: &Path) {
+        let Some(id) = self.doc_ids.remove(path) else {
+            return;
+        };
+        let Some(doc) = self.docs[id].take() else {
+            return;
+        };
+        self.total_length -= doc.length;
+        for term in doc.terms {
+            if let Some(docs) = self.postings.get_mut(&term) {
+                docs.remove(&id);
+                if docs.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+        self.free.push(id);
+    }
+
+    /// Number of indexed notes.
+    pub fn len(&self) -> usize {
+        self.doc_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doc_ids.is_empty()
+    }
+
+    /// Run a query and return matching notes, best first.
+    ///
+    /// Supports bare terms, `"exact phrases"`, `prefix*`, `AND`, `OR`,
+    /// `NOT`/`-term` and parentheses; adjacent terms are ANDed.
+    pub fn search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let Some(query) = SearchQuery::parse(query)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut hits: Vec<SearchHit> = self
+            .eval(&query)
+            .into_iter()
+            .filter_map(|(id, result)| {
+                let doc = self.docs[id].as_ref()?;
+                Some(SearchHit {
+                    path: doc.path.clone(),
+                    score: result.score,
+                    lines: line_snippets(&doc.content, result.spans),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        Ok(hits)
+    }
+
+    fn eval(&self, query: &SearchQuery) -> HashMap<DocId, DocResult> {
+        match query {
+            SearchQuery::Term(term) => self.score_terms(std::slice::from_ref(term)),
+            SearchQuery::Prefix(prefix) => {
+                let terms: Vec<String> = self
+                    .postings
+                    .range(prefix.clone()..)
+                    .take_while(|(term, _)| term.starts_with(prefix.as_str()))
+                    .map(|(term, _)| term.clone())
+                    .collect();
+                self.score_terms(&terms)
+            }
+            SearchQuery::Phrase(words) => self.eval_phrase(words),
+            SearchQuery::And(parts) => {
+                let mut parts = parts.iter().map(|p| self.eval(p));
+                let Some(mut acc) = parts.next() else {
+                    return HashMap::new();
+                };
+                for part in parts {
+                    acc.retain(|id, _| part.contains_key(id));
+                    for (id, result) in part {
+                        if let Some(existing) = acc.get_mut(&id) {
+                            existing.merge(result);
+                        }
+                    }
+                }
+                acc
+            }
+            SearchQuery::Or(parts) => {
+                let mut acc: HashMap<DocId, DocResult> = HashMap::new();
+                for part in parts {
+                    for (id, result) in self.eval(part) {
+                        acc.entry(id).or_default().merge(result);
+                    }
+                }
+                acc
+            }
+            SearchQuery::Not(inner) => {
+                let excluded = self.eval(inner);
+                self.doc_ids
+                    .values()
+                    .filter(|id| !excluded.contains_key(id))
+                    .map(|&id| (id, DocResult::default()))
+                    .collect()
+            }
+        }
+    }
+
+    /// BM25 score for documents containing any of `terms`.
+    fn score_terms(&self, terms: &[String]) -> HashMap<DocId, DocResult> {
+        let mut results: HashMap<DocId, DocResult> = HashMap::new();
+        for term in terms {
+            let Some(docs) = self.postings.get(term) else {
+                continue;
+            };
+            let idf = self.idf(docs.len());
+            for (&id, postings) in docs {
+                let result = results.entry(id).or_default();
+                result.score += idf * self.tf_weight(id, postings.len());
+                result.spans.extend(postings.iter().map(|p| p.span.clone()));
+            }
+        }
+        results
+    }
+
+    fn eval_phrase(&self, words: &[String]) -> HashMap<DocId, DocResult> {
+        let Some(first) = words.first().and_then(|w| self.postings.get(w)) else {
+            return HashMap::new();
+        };
+
+        let mut matches: HashMap<DocId, Vec<Range<usize>>> = HashMap::new();
+        for (&id, start_postings) in first {
+            // Positions of each following word in this document
+            let rest: Option<Vec<HashMap<usize, &Posting>>> = words[1..]
+                .iter()
+                .map(|w| {
+                    let postings = self.postings.get(w)?.get(&id)?;
+                    Some(postings.iter().map(|p| (p.position, p)).collect())
+                })
+                .collect();
+            let Some(rest) = rest else {
+                continue;
+            };
+
+            for start in start_postings {
+                let end = rest
+                    .iter()
+                    .enumerate()
+                    .try_fold(&start.span, |_, (i, positions)| {
+                        positions.get(&(start.position + i + 1)).map(|p| &p.span)
+                    });
+                if let Some(end) = end {
+                    matches
+                        .entry(id)
+                        .or_default()
+                        .push(start.span.start..end.end);
+                }
+            }
+        }
+
+        let idf = self.idf(matches.len());
+        matches
+            .into_iter()
+            .map(|(id, spans)| {
+                let score = idf * self.tf_weight(id, spans.len());
+                (id, DocResult { score, spans })
+            })
+            .collect()
+    }
+
+    fn idf(&self, doc_freq: usize) -> f64 {
+        let n = self.len() as f64;
+        let df = doc_freq as f64;
+        ((n - df + 0.5) / (df + 0.5) + 1.0).ln()
+    }
+
+    fn tf_weight(&self, id: DocId, tf: usize) -> f64 {
+        let length = self.docs[id].as_ref().map_or(0, |d| d.length) as f64;
+        let avg = self.total_length as f64 / self.len().max(1) as f64;
+        let tf = tf as f64;
+        tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length / avg.max(1.0)))
+    }
+}
+
+#[derive(Debug, Default)]
+struct DocResult {
+    score: f64,
+    spans: Vec<Range<usize>>,
+}
+
+impl DocResult {
+    fn merge(&mut self, other: DocResult) {
+        self.score += other.score;
+        self.spans.extend(other.spans);
+    }
+}
+
+/// Group match spans by line and build one snippet per line.
+fn line_snippets(content: &str, mut spans: Vec<Range<usize>>) -> Vec<Snippet> {
+    spans.sort_by_key(|s| s.start);
+    let mut seen_lines = HashSet::new();
+    spans
+        .into_iter()
+        .map(|span| Snippet::at(content, span))
+        .filter(|snippet| seen_lines.insert(snippet.line))
+        .collect()
+}
+
+/// Split text into lowercase alphanumeric terms with their byte spans.
+fn tokenize(text: &str) -> impl Iterator<Item = (String, Range<usize>)> + '_ {
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while chars.peek().is_some_and(|(_, c)| !c.is_alphanumeric()) {
+            chars.next();
+        }
+        let (start, _) = *chars.peek()?;
+        let mut end = start;
+        while let Some(&(i, c)) = chars.peek() {
+            if !c.is_alphanumeric() {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        Some((text[start..end].to_lowercase(), start..end))
+    })
+}
+
+/// A parsed full-text query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchQuery {
+    Term(String),
+    Prefix(String),
+    Phrase(Vec<String>),
+    And(Vec<SearchQuery>),
+    Or(Vec<SearchQuery>),
+    Not(Box<SearchQuery>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl SearchQuery {
+    /// Parse a query string. Returns `None` for an empty query.
+    pub fn parse(input: &str) -> Result<Option<Self>> {
+        let tokens = lex(input);
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+        let mut parser = QueryParser { tokens, pos: 0 };
+        let query = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(Error::InvalidQuery(format!(
+                "unexpected {:?}",
+                parser.tokens[parser.pos]
+            )));
+        }
+        Ok(query)
+    }
+}
+
+fn lex(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            '"' => {
+                chars.next();
+                // An unterminated quote runs to the end of the input
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                tokens.push(Token::Quoted(phrase));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '"') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    tokens
+}
+
+struct QueryParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl QueryParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<Option<SearchQuery>> {
+        let mut parts: Vec<SearchQuery> = self.parse_and()?.into_iter().collect();
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            parts.extend(self.parse_and()?);
+        }
+        Ok(match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => Some(SearchQuery::Or(parts)),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Option<SearchQuery>> {
+        let mut parts = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::Or) | Some(Token::Close) => break,
+                Some(Token::And) => self.pos += 1,
+                _ => parts.extend(self.parse_unary()?),
+            }
+        }
+        Ok(match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => Some(SearchQuery::And(parts)),
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Option<SearchQuery>> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Ok(None);
+        };
+        self.pos += 1;
+        match token {
+            Token::Not => Ok(self.parse_unary()?.map(|q| SearchQuery::Not(Box::new(q)))),
+            Token::Open => {
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(Error::InvalidQuery("missing closing parenthesis".into()));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Token::Quoted(phrase) => {
+                let words: Vec<String> = tokenize(&phrase).map(|(t, _)| t).collect();
+                Ok(match words.len() {
+                    0 => None,
+                    1 => Some(SearchQuery::Term(words[0].clone())),
+                    _ => Some(SearchQuery::Phrase(words)),
+                })
+            }
+            Token::Word(word) => {
+                let (word, prefix) = match word.strip_suffix('*') {
+                    Some(stem) => (stem, true),
+                    None => (word.as_str(), false),
+                };
+                let mut terms: Vec<String> = tokenize(word).map(|(t, _)| t).collect();
+                Ok(match (terms.len(), prefix) {
+                    (0, _) => None,
+                    (1, true) => Some(SearchQuery::Prefix(terms.remove(0))),
+                    (1, false) => Some(SearchQuery::Term(terms.remove(0))),
+                    // Punctuated words like `foo-bar` behave as a phrase
+                    _ => Some(SearchQuery::Phrase(terms)),
+                })
+            }
+            Token::And | Token::Or | Token::Close => {
+                Err(Error::InvalidQuery(format!("unexpected {:?}", token)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SearchIndex {
+        let mut index = SearchIndex::new();
+        index.update_document(
+            Path::new("rust.md"),
+            "# Rust\n\nRust is a systems language.\nThe borrow checker keeps memory safe.",
+        );
+        index.update_document(
+            Path::new("go.md"),
+            "# Go\n\nGo is a systems language with garbage collection.",
+        );
+        index.update_document(Path::new("notes.md"), "# Notes\n\nMemory and rusty tools.");
+        index
+    }
+
+    fn paths(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.path.to_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = SearchQuery::parse("rust OR go -\"garbage collection\" mem*")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            query,
+            SearchQuery::Or(vec![
+                SearchQuery::Term("rust".into()),
+                SearchQuery::And(vec![
+                    SearchQuery::Term("go".into()),
+                    SearchQuery::Not(Box::new(SearchQuery::Phrase(vec![
+                        "garbage".into(),
+                        "collection".into()
+                    ]))),
+                    SearchQuery::Prefix("mem".into()),
+                ]),
+            ])
+        );
+        assert!(SearchQuery::parse("(rust").is_err());
+        assert_eq!(SearchQuery::parse("  ").unwrap(), None);
+    }
+
+    #[test]
+    fn test_term_and_phrase_search() {
+        let index = index();
+
+        let hits = index.search("systems language").unwrap();
+        assert_eq!(hits.len(), 2);
+
+        let hits = index.search("\"borrow checker\"").unwrap();
+        assert_eq!(paths(&hits), vec!["rust.md"]);
+        assert_eq!(hits[0].lines[0].line, 4);
+        assert_eq!(
+            &hits[0].lines[0].text[hits[0].lines[0].highlight.clone()],
+            "borrow checker"
+        );
+
+        assert!(index.search("\"checker borrow\"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_prefix_and_boolean_search() {
+        let index = index();
+
+        let hits = index.search("rust*").unwrap();
+        assert_eq!(paths(&hits).len(), 2);
+        // "Rust" appears twice in rust.md, so it ranks first
+        assert_eq!(hits[0].path, PathBuf::from("rust.md"));
+
+        let hits = index.search("systems NOT garbage").unwrap();
+        assert_eq!(paths(&hits), vec!["rust.md"]);
+
+        let hits = index.search("memory AND (rusty OR borrow)").unwrap();
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let mut index = index();
+        index.update_document(Path::new("go.md"), "# Go\n\nNow about channels.");
+        assert!(index.search("garbage").unwrap().is_empty());
+        assert_eq!(paths(&index.search("channels").unwrap()), vec!["go.md"]);
+
+        index.remove_document(Path::new("rust.md"));
+        assert_eq!(index.len(), 2);
+        assert!(index.search("borrow").unwrap().is_empty());
+        assert!(!index.postings.contains_key("borrow"));
+
+        // The removed note's slot is reused rather than left behind
+        index.update_document(Path::new("c.md"), "# C\n\nBorrowed memory.");
+        assert_eq!(index.docs.len(), 3);
+        assert_eq!(paths(&index.search("borrowed").unwrap()), vec!["c.md"]);
+        assert_eq!(index.search("memory").unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,58 @@
+use std::ops::Range;
+
+/// A line of context taken from a note, with a highlighted range inside it.
+///
+/// Only the line is kept, not the whole paragraph: snippets are drawn one
+/// row each in the backlinks, mentions and search panes, search groups its
+/// hits by line, and the CLI prints them as `path:line`. In markdown a
+/// paragraph is often a single long line anyway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// 1-based line number in the source.
+    pub line: usize,
+    /// The line text, with surrounding whitespace trimmed.
+    pub text: String,
+    /// Byte range inside `text` to highlight.
+    pub highlight: Range<usize>,
+}
+
+impl Snippet {
+    /// Build a snippet for the line containing `span` in `content`.
+    /// The highlight is clamped to that line if the span crosses a newline.
+    pub fn at(content: &str, span: Range<usize>) -> Self {
+        let start = span.start.min(content.len());
+        let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = content[start..]
+            .find('\n')
+            .map_or(content.len(), |i| start + i);
+        let raw = &content[line_start..line_end];
+
+        let leading = raw.len() - raw.trim_start().len();
+        let text = raw.trim();
+        let hl_start = (start - line_start).saturating_sub(leading).min(text.len());
+        let hl_end = (span.end.min(line_end) - line_start)
+            .saturating_sub(leading)
+            .clamp(hl_start, text.len());
+
+        Self {
+            line: content[..line_start].matches('\n').count() + 1,
+            text: text.to_string(),
+            highlight: hl_start..hl_end,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_at() {
+        let content = "# Title\n\n  - see [[Other]] here\nlast";
+        let start = content.find("[[").unwrap();
+        let snippet = Snippet::at(content, start..start + 9);
+        assert_eq!(snippet.line, 3);
+        assert_eq!(snippet.text, "- see [[Other]] here");
+        assert_eq!(&snippet.text[snippet.highlight.clone()], "[[Other]]");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{Error, NoteGraph};
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// What to order a list of notes by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    #[default]
+    Path,
+    Title,
+    Modified,
+    Created,
+    Backlinks,
+    Words,
+    /// PageRank.
+    Importance,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 7] = [
+        SortKey::Path,
+        SortKey::Title,
+        SortKey::Modified,
+        SortKey::Created,
+        SortKey::Backlinks,
+        SortKey::Words,
+        SortKey::Importance,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortKey::Path => "path",
+            SortKey::Title => "title",
+            SortKey::Modified => "modified",
+            SortKey::Created => "created",
+            SortKey::Backlinks => "backlinks",
+            SortKey::Words => "words",
+            SortKey::Importance => "importance",
+        }
+    }
+
+    /// The key after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let pos = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(pos + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::Config(format!("unknown sort key '{s}'")))
+    }
+}
+
+/// A sort key and direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SortOrder {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl SortOrder {
+    /// Sort notes in place. Ties are broken by path, always ascending.
+    pub fn sort(&self, graph: &NoteGraph, notes: &mut [NodeIndex]) {
+        notes.sort_by(|&a, &b| {
+            let (Some(note_a), Some(note_b)) = (graph.get_note(a), graph.get_note(b)) else {
+                return Ordering::Equal;
+            };
+            let ord = match self.key {
+                SortKey::Path => Ordering::Equal,
+                SortKey::Title => note_a
+                    .title
+                    .to_lowercase()
+                    .cmp(&note_b.title.to_lowercase()),
+                SortKey::Modified => note_a.meta.modified.cmp(&note_b.meta.modified),
+                SortKey::Created => note_a.meta.created.cmp(&note_b.meta.created),
+                SortKey::Backlinks => graph.backlinks(a).len().cmp(&graph.backlinks(b).len()),
+                SortKey::Words => note_a.meta.words.cmp(&note_b.meta.words),
+                SortKey::Importance => {
+                    let ranking = graph.ranking();
+                    ranking.pagerank(a).total_cmp(&ranking.pagerank(b))
+                }
+            };
+            let ord = if self.descending && self.key != SortKey::Path {
+                ord.reverse()
+            } else {
+                ord
+            };
+            ord.then_with(|| {
+                let by_path = note_a.path.cmp(&note_b.path);
+                if self.descending && self.key == SortKey::Path {
+                    by_path.reverse()
+                } else {
+                    by_path
+                }
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NoteMeta, Result};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_sort_order() -> Result<()> {
+        let mut graph = NoteGraph::new();
+        let a = graph.add_note(PathBuf::from("a.md"), "Zeta".to_string());
+        let b = graph.add_note(PathBuf::from("b.md"), "alpha".to_string());
+        let c = graph.add_note(PathBuf::from("c.md"), "Beta".to_string());
+        graph.add_link(a, c);
+        graph.add_link(b, c);
+        graph.add_link(c, b);
+        for (idx, words) in [(a, 10), (b, 30), (c, 20)] {
+            graph.set_meta(
+                idx,
+                NoteMeta {
+                    words,
+                    ..NoteMeta::default()
+                },
+            );
+        }
+        graph.update_ranking();
+
+        let sorted = |order: SortOrder| {
+            let mut notes = vec![c, a, b];
+            order.sort(&graph, &mut notes);
+            notes
+        };
+        let order = |key, descending| SortOrder { key, descending };
+        assert_eq!(sorted(order(SortKey::Path, false)), vec![a, b, c]);
+        assert_eq!(sorted(order(SortKey::Path, true)), vec![c, b, a]);
+        assert_eq!(sorted(order(SortKey::Title, false)), vec![b, c, a]);
+        assert_eq!(sorted(order(SortKey::Backlinks, true)), vec![c, b, a]);
+        assert_eq!(sorted(order(SortKey::Words, false)), vec![a, c, b]);
+        // Equal keys fall back to path order
+        assert_eq!(sorted(order(SortKey::Modified, true)), vec![a, b, c]);
+
+        assert_eq!("Backlinks".parse::<SortKey>()?, SortKey::Backlinks);
+        assert!("size".parse::<SortKey>().is_err());
+        assert_eq!(SortKey::Importance.next(), SortKey::Path);
+        assert_eq!(sorted(order(SortKey::Importance, true)), vec![c, b, a]);
+        Ok(())
+    }
+}
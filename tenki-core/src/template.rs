@@ -0,0 +1,198 @@
+use crate::Result;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use walkdir::WalkDir;
+
+/// Folder inside the vault holding note templates. Notes in it are not
+/// part of the graph.
+pub const TEMPLATES_DIR: &str = "templates";
+
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*(\w+)(?::([^}]*))?\s*\}\}").unwrap());
+
+/// A note template. Placeholders in `{{...}}` are filled in when a note is
+/// created from it:
+///
+/// - `{{title}}`: the new note's title
+/// - `{{date}}`, `{{time}}`: the current date and time, with an optional
+///   strftime format as in `{{date:%d %B %Y}}`
+/// - `{{uuid}}`: a random UUID
+/// - `{{prompt:Question}}`: an answer asked for when the note is created
+///
+/// Anything else is left as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    /// Path inside the templates folder, without `.md`.
+    pub name: String,
+    pub content: String,
+}
+
+/// What to fill a template's placeholders with.
+#[derive(Debug, Clone)]
+pub struct TemplateValues {
+    pub title: String,
+    pub now: DateTime<Local>,
+    /// Answers to `{{prompt:...}}` placeholders, by question.
+    pub answers: HashMap<String, String>,
+}
+
+impl TemplateValues {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            now: Local::now(),
+            answers: HashMap::new(),
+        }
+    }
+}
+
+impl Template {
+    pub fn new(name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            content: content.into(),
+        }
+    }
+
+    /// The templates folder of a vault.
+    pub fn dir(root: &Path) -> PathBuf {
+        root.join(TEMPLATES_DIR)
+    }
+
+    /// Every template in the vault, sorted by name.
+    pub fn load_all(root: &Path) -> Result<Vec<Template>> {
+        let dir = Self::dir(root);
+        let mut templates = Vec::new();
+        for entry in WalkDir::new(&dir).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            let name = path
+                .strip_prefix(&dir)
+                .unwrap_or(path)
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            templates.push(Template::new(name, fs::read_to_string(path)?));
+        }
+        templates.sort_by_key(|t| t.name.to_lowercase());
+        Ok(templates)
+    }
+
+    /// Find a template by name (case-insensitive).
+    pub fn find(root: &Path, name: &str) -> Result<Option<Template>> {
+        Ok(Self::load_all(root)?
+            .into_iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name)))
+    }
+
+    /// The questions of the template's `{{prompt:...}}` placeholders, each
+    /// once, in order of appearance.
+    pub fn prompts(&self) -> Vec<String> {
+        let mut prompts: Vec<String> = Vec::new();
+        for cap in PLACEHOLDER_RE.captures_iter(&self.content) {
+            if &cap[1] == "prompt"
+                && let Some(question) = cap.get(2).map(|m| m.as_str().trim())
+                && !question.is_empty()
+                && !prompts.iter().any(|p| p == question)
+            {
+                prompts.push(question.to_string());
+            }
+        }
+        prompts
+    }
+
+    /// The template with its placeholders filled in.
+    pub fn render(&self, values: &TemplateValues) -> String {
+        PLACEHOLDER_RE
+            .replace_all(&self.content, |cap: &Captures| {
+                let arg = cap.get(2).map(|m| m.as_str().trim());
+                let formatted = |default: &str| {
+                    let format = arg.filter(|f| !f.is_empty()).unwrap_or(default);
+                    // An invalid format would panic when displayed
+                    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                        cap[0].to_string()
+                    } else {
+                        values.now.format(format).to_string()
+                    }
+                };
+                match &cap[1] {
+                    "title" => values.title.clone(),
+                    "date" => formatted("%Y-%m-%d"),
+                    "time" => formatted("%H:%M"),
+                    "uuid" => uuid::Uuid::new_v4().to_string(),
+                    "prompt" => arg
+                        .and_then(|question| values.answers.get(question))
+                        .cloned()
+                        .unwrap_or_default(),
+                    _ => cap[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Scanner;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_render() {
+        let template = Template::new(
+            "meeting",
+            "---\nid: {{uuid}}\n---\n# {{ title }}\n\n{{date}} {{time}} ({{date:%A}})\n\
+             With: {{prompt:Who}}\nAbout: {{prompt: Topic }}\nAgain: {{prompt:Who}}\n\
+             {{unknown}} {{date:%Q}}\n",
+        );
+        assert_eq!(template.prompts(), vec!["Who", "Topic"]);
+
+        let mut values = TemplateValues::new("Weekly sync");
+        values.now = Local.with_ymd_and_hms(2026, 10, 17, 9, 30, 0).unwrap();
+        values.answers.insert("Who".into(), "Aiko".into());
+        let rendered = template.render(&values);
+        let (id, rest) = rendered
+            .strip_prefix("---\nid: ")
+            .and_then(|r| r.split_once('\n'))
+            .unwrap();
+        assert_eq!(id.len(), 36);
+        assert_eq!(
+            rest,
+            "---\n# Weekly sync\n\n2026-10-17 09:30 (Saturday)\n\
+             With: Aiko\nAbout: \nAgain: Aiko\n{{unknown}} {{date:%Q}}\n"
+        );
+    }
+
+    #[test]
+    fn test_templates_folder() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("templates/work"))?;
+        fs::write(root.join("templates/Daily.md"), "# {{date}}\n")?;
+        fs::write(root.join("templates/work/meeting.md"), "# {{title}}\n")?;
+        fs::write(root.join("note.md"), "# Note\n")?;
+
+        let names: Vec<String> = Template::load_all(root)?
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["Daily", "work/meeting"]);
+        assert!(Template::find(root, "daily")?.is_some());
+
+        // Templates aren't notes
+        let scanner = Scanner::new(root);
+        assert_eq!(scanner.scan()?.note_count(), 1);
+        assert_eq!(scanner.list_files()?.len(), 1);
+        Ok(())
+    }
+}
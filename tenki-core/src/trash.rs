@@ -0,0 +1,226 @@
+use crate::config::TENKI_DIR;
+use crate::{Error, NoteGraph, Result, Scanner};
+use chrono::Local;
+use petgraph::graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A deleted note waiting in `.tenki/trash`. The note is stored as
+/// `<id>.md` next to an `<id>.toml` file holding the rest of this entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    #[serde(skip)]
+    pub id: String,
+    pub title: String,
+    /// Where the note was, relative to the vault root.
+    pub path: PathBuf,
+    /// Local time of deletion, `YYYY-MM-DD HH:MM:SS`.
+    pub deleted: String,
+}
+
+impl TrashEntry {
+    /// Directory holding deleted notes.
+    pub fn dir(root: &Path) -> PathBuf {
+        root.join(TENKI_DIR).join("trash")
+    }
+
+    fn note_file(&self, root: &Path) -> PathBuf {
+        Self::dir(root).join(format!("{}.md", self.id))
+    }
+
+    fn meta_file(&self, root: &Path) -> PathBuf {
+        Self::dir(root).join(format!("{}.toml", self.id))
+    }
+}
+
+/// A note just moved to the trash.
+#[derive(Debug)]
+pub struct Trashed {
+    pub entry: TrashEntry,
+    /// Notes linking to it that couldn't be re-read afterwards. Their links
+    /// still show up as unresolved, under the deleted note's title.
+    pub errors: Vec<(PathBuf, Error)>,
+}
+
+impl Scanner {
+    /// Move a note to the trash and drop it from the graph. Notes linking to
+    /// it are re-read so their links show up as unresolved; once the note is
+    /// moved, failing to re-read one doesn't fail the whole deletion.
+    pub fn trash_note(&self, graph: &mut NoteGraph, idx: NodeIndex) -> Result<Trashed> {
+        let note = graph
+            .get_note(idx)
+            .ok_or_else(|| Error::NoteNotFound(format!("{idx:?}")))?;
+        let relative = note
+            .path
+            .strip_prefix(self.root())
+            .map_err(|_| Error::InvalidPath(note.path.display().to_string()))?
+            .to_path_buf();
+        let now = Local::now();
+        let stem = note
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut entry = TrashEntry {
+            id: format!("{}-{stem}", now.format("%Y%m%d-%H%M%S")),
+            title: note.title.clone(),
+            path: relative,
+            deleted: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+        };
+        let base = entry.id.clone();
+        let mut n = 1;
+        while entry.note_file(self.root()).exists() {
+            n += 1;
+            entry.id = format!("{base}-{n}");
+        }
+
+        fs::create_dir_all(TrashEntry::dir(self.root()))?;
+        let meta = toml::to_string_pretty(&entry).map_err(|e| Error::Config(e.to_string()))?;
+        fs::rename(&note.path, entry.note_file(self.root()))?;
+        // A trashed note without its meta file can't be listed or restored
+        if let Err(e) = fs::write(entry.meta_file(self.root()), meta) {
+            let _ = fs::rename(entry.note_file(self.root()), &note.path);
+            return Err(e.into());
+        }
+
+        let linking: Vec<PathBuf> = graph
+            .backlinks(idx)
+            .into_iter()
+            .map(|n| n.path.clone())
+            .filter(|path| path != &note.path)
+            .collect();
+        graph.remove_note(idx);
+        let mut errors = Vec::new();
+        for path in linking {
+            if let Err(e) = self.update_file(graph, &path) {
+                errors.push((path, e));
+            }
+        }
+        graph.update_ranking();
+        Ok(Trashed { entry, errors })
+    }
+
+    /// Notes in the trash, most recently deleted first.
+    pub fn trash_entries(&self) -> Result<Vec<TrashEntry>> {
+        let dir = TrashEntry::dir(self.root());
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|ext| ext != "toml") {
+                continue;
+            }
+            let text = fs::read_to_string(&path)?;
+            let mut entry: TrashEntry =
+                toml::from_str(&text).map_err(|e| Error::Config(e.to_string()))?;
+            entry.id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if entry.note_file(self.root()).exists() {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|a, b| b.deleted.cmp(&a.deleted).then(b.id.cmp(&a.id)));
+        Ok(entries)
+    }
+
+    /// Put a deleted note back where it was and add it to the graph again.
+    pub fn restore_note(&self, graph: &mut NoteGraph, entry: &TrashEntry) -> Result<NodeIndex> {
+        let path = self.root().join(&entry.path);
+        if path.exists() {
+            return Err(Error::NoteExists(path.display().to_string()));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(entry.note_file(self.root()), &path)?;
+        fs::remove_file(entry.meta_file(self.root()))?;
+
+        let idx = self.update_file(graph, &path)?;
+        graph.update_ranking();
+        Ok(idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trash_and_restore() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("sub"))?;
+        fs::write(root.join("a.md"), "# A\n\nSee [[B]] and [[C]].")?;
+        fs::write(root.join("sub/b.md"), "# B\n\nBack to [[A]].")?;
+        fs::write(root.join("c.md"), "# C\n")?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        let b = graph.find_by_title("B").unwrap();
+        let trashed = scanner.trash_note(&mut graph, b)?;
+        assert!(trashed.errors.is_empty());
+        let entry = trashed.entry;
+        assert_eq!(entry.path, Path::new("sub/b.md"));
+        assert!(!root.join("sub/b.md").exists());
+
+        // The graph is still consistent after indices shift
+        assert_eq!(graph.note_count(), 2);
+        assert_eq!(graph.find_by_title("B"), None);
+        let a = graph.find_by_title("A").unwrap();
+        let c = graph.find_by_title("C").unwrap();
+        assert_eq!(graph.find_by_path(&root.join("c.md")), Some(c));
+        assert!(graph.has_link(a, c));
+        assert_eq!(graph.unresolved_links(a)[0].target, "B");
+        // Trashed notes aren't scanned
+        assert_eq!(scanner.scan()?.note_count(), 2);
+
+        let entries = scanner.trash_entries()?;
+        assert_eq!(entries, vec![entry.clone()]);
+
+        let b = scanner.restore_note(&mut graph, &entry)?;
+        assert_eq!(
+            fs::read_to_string(root.join("sub/b.md"))?,
+            "# B\n\nBack to [[A]]."
+        );
+        assert!(graph.has_link(a, b));
+        assert!(graph.has_link(b, a));
+        assert!(graph.unresolved_links(a).is_empty());
+        assert!(scanner.trash_entries()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trash_with_unreadable_backlink() -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.md"), "# A\n\nSee [[B]].")?;
+        fs::write(root.join("b.md"), "# B\n")?;
+        fs::write(root.join("c.md"), "# C\n\nAlso [[B]].")?;
+
+        let scanner = Scanner::new(root);
+        let mut graph = scanner.scan()?;
+        // A turns into something that can't be read as text after the scan
+        fs::write(root.join("a.md"), b"# A\n\xff\xfe [[B]]")?;
+
+        let b = graph.find_by_title("B").unwrap();
+        let trashed = scanner.trash_note(&mut graph, b)?;
+        assert!(!root.join("b.md").exists());
+        assert_eq!(scanner.trash_entries()?, vec![trashed.entry.clone()]);
+        assert_eq!(trashed.errors.len(), 1);
+        assert_eq!(trashed.errors[0].0, root.join("a.md"));
+
+        // Both notes' links are still recorded as unresolved
+        let a = graph.find_by_title("A").unwrap();
+        let c = graph.find_by_title("C").unwrap();
+        assert_eq!(graph.unresolved_links(a)[0].target, "B");
+        assert_eq!(graph.unresolved_links(c)[0].target, "B");
+        assert_eq!(graph.note_count(), 2);
+        Ok(())
+    }
+}
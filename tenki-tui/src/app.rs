@@ -1,42 +1,238 @@
+use crate::calendar::{Calendar, DayView};
+use crate::chain::LinkChain;
+use crate::completion::LinkCompletion;
+use crate::editor::ExternalEdit;
+use crate::finder::{Finder, FinderAction};
+use crate::health::HealthView;
+use crate::history::History;
+use crate::input::Input;
+use crate::local_graph::LocalGraph;
+use crate::note_editor::{EditorAction, Mode, NoteEditor};
+use crate::rename::RenamePreview;
+use crate::search::SearchPanel;
+use crate::templates::{PendingNote, TemplatePicker};
+use crate::trash::{DeleteConfirm, TrashView, Undo};
+use crate::tree::tree_rows;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashSet;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
-use tenki_core::{NoteGraph, Scanner};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use tenki_core::mentions::link_mention;
+use tenki_core::{
+    Error, Mention, NoteGraph, Period, PeriodicNote, Query, Related, Scanner, Snippet, Template,
+    TemplateValues, TrashEntry, VaultConfig, WikiLink, extract_wikilink_spans, unlinked_mentions,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Pane {
     Files,
+    Calendar,
     Preview,
     Backlinks,
+    Related,
+    Mentions,
 }
 
 impl Pane {
     pub fn next(self) -> Self {
         match self {
-            Pane::Files => Pane::Preview,
+            Pane::Files => Pane::Calendar,
+            Pane::Calendar => Pane::Preview,
             Pane::Preview => Pane::Backlinks,
-            Pane::Backlinks => Pane::Files,
+            Pane::Backlinks => Pane::Related,
+            Pane::Related => Pane::Mentions,
+            Pane::Mentions => Pane::Files,
         }
     }
 
     pub fn prev(self) -> Self {
         match self {
-            Pane::Files => Pane::Backlinks,
-            Pane::Preview => Pane::Files,
+            Pane::Files => Pane::Mentions,
+            Pane::Calendar => Pane::Files,
+            Pane::Preview => Pane::Calendar,
             Pane::Backlinks => Pane::Preview,
+            Pane::Related => Pane::Backlinks,
+            Pane::Mentions => Pane::Related,
         }
     }
 }
 
+/// A note linking to the selected note, with the lines that contain the links.
+pub struct BacklinkEntry {
+    pub title: String,
+    pub path: PathBuf,
+    pub snippets: Vec<Snippet>,
+    pub expanded: bool,
+}
+
+/// A row in the Files pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileRow {
+    /// A saved search shown as a virtual folder. `count` is `None` when its
+    /// query no longer parses.
+    Saved {
+        index: usize,
+        count: Option<usize>,
+        expanded: bool,
+    },
+    /// A note listed under an expanded saved search.
+    SavedNote(PathBuf),
+    /// A folder in the tree view, with the number of notes below it.
+    Dir {
+        path: PathBuf,
+        depth: usize,
+        count: usize,
+        expanded: bool,
+    },
+    Note {
+        path: PathBuf,
+        depth: usize,
+    },
+}
+
+impl FileRow {
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            FileRow::Saved { .. } | FileRow::Dir { .. } => None,
+            FileRow::SavedNote(path) | FileRow::Note { path, .. } => Some(path),
+        }
+    }
+
+    /// Whether two rows refer to the same entry, ignoring counts and expansion.
+    fn same_entry(&self, other: &FileRow) -> bool {
+        match (self, other) {
+            (FileRow::Saved { index: a, .. }, FileRow::Saved { index: b, .. }) => a == b,
+            (FileRow::SavedNote(a), FileRow::SavedNote(b))
+            | (FileRow::Dir { path: a, .. }, FileRow::Dir { path: b, .. })
+            | (FileRow::Note { path: a, .. }, FileRow::Note { path: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// What a status-bar prompt is asking for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// A structured query to filter the Files pane.
+    Query,
+    /// A name to save the active query under.
+    SaveSearch,
+    /// A substring narrowing the Files pane, applied while typing.
+    Filter,
+    /// The title of a note to create, optionally after a folder path.
+    NewNote,
+    /// A new title and folder for the selected note.
+    Rename,
+    /// An answer to a `{{prompt:...}}` in the template of a new note.
+    TemplateField,
+}
+
+impl PromptKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            PromptKind::Query => "query:",
+            PromptKind::SaveSearch => "save search as:",
+            PromptKind::Filter => "filter:",
+            PromptKind::NewNote => "new note:",
+            PromptKind::Rename => "rename to:",
+            PromptKind::TemplateField => "answer:",
+        }
+    }
+}
+
+/// A one-line prompt shown in the status bar.
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: Input,
+}
+
+/// A note related to the selected one, with why.
+pub struct RelatedEntry {
+    pub title: String,
+    pub path: PathBuf,
+    /// What the notes have in common, e.g. `cited together 2× · #idea`.
+    pub reason: String,
+}
+
+/// How many related notes the Related pane lists.
+const RELATED_LIMIT: usize = 10;
+
+/// A modal popup that receives all keys while open.
+pub enum Popup {
+    Finder(Finder),
+    Search(SearchPanel),
+    Prompt(Prompt),
+    Health(HealthView),
+    Chain(LinkChain),
+    Graph(LocalGraph),
+    Rename(RenamePreview),
+    Delete(DeleteConfirm),
+    Trash(TrashView),
+    Templates(TemplatePicker),
+    Day(DayView),
+}
+
 pub struct App {
     pub scanner: Scanner,
     pub graph: NoteGraph,
     pub files: Vec<PathBuf>,
+    /// Rows shown in the Files pane; `file_list_state` indexes into this.
+    pub view: Vec<FileRow>,
+    pub config: VaultConfig,
+    /// Names of the saved searches currently expanded in the Files pane.
+    pub expanded_searches: HashSet<String>,
+    /// Show notes as a folder tree rather than a flat list of paths.
+    pub tree_view: bool,
+    /// Folders currently expanded in the tree view. Unlike the sort order
+    /// this isn't saved to the vault config: folders open and close as notes
+    /// are followed, and writing the config on each of those would churn a
+    /// file that may be shared with others.
+    pub expanded_dirs: HashSet<PathBuf>,
+    /// Structured query whose results replace the file list until cleared.
+    pub query: Option<Query>,
+    /// Case-insensitive substring of title or path the Files pane is narrowed to.
+    pub filter: String,
     pub active_pane: Pane,
     pub file_list_state: usize,
     pub backlink_list_state: usize,
     pub selected_content: String,
-    pub backlinks: Vec<String>,
+    /// Wikilinks in the previewed note, selectable in the preview pane.
+    pub preview_links: Vec<WikiLink>,
+    pub link_list_state: usize,
+    /// Line (1-based) to scroll to and highlight after a jump, e.g. from search.
+    pub jump_line: Option<usize>,
+    pub history: History,
+    pub backlinks: Vec<BacklinkEntry>,
+    pub mention_list_state: usize,
+    pub mentions: Vec<Mention>,
+    /// The note `mentions` were looked up for. Finding them reads the whole
+    /// vault, so it waits until the Mentions pane is focused.
+    pub mentions_for: Option<PathBuf>,
+    pub related_list_state: usize,
+    pub related: Vec<RelatedEntry>,
+    pub popup: Option<Popup>,
+    /// The last search, kept for reopening the panel and `n`/`N`.
+    pub last_search: Option<SearchPanel>,
+    /// One-line message shown in the status bar until the next action.
+    pub message: Option<String>,
+    /// Note to open in $EDITOR once the current key has been handled.
+    pub pending_edit: Option<PathBuf>,
+    /// The last delete or rename, for `u`.
+    pub undo: Option<Undo>,
+    /// A note waiting for its template prompts to be answered.
+    pub pending_note: Option<PendingNote>,
+    /// The calendar pane below the Files pane, when shown.
+    pub calendar: Option<Calendar>,
+    /// The built-in editor, shown in place of the preview while open.
+    pub editor: Option<NoteEditor>,
+    /// Suggestions for the `[[link` being typed in the editor or a prompt.
+    pub completion: Option<LinkCompletion>,
+    /// Set by `Esc` on the suggestions, until the link is left.
+    completion_dismissed: bool,
+    /// Notes open in GUI editors, re-read whenever they are saved.
+    pub external_edits: Vec<ExternalEdit>,
 }
 
 impl App {
@@ -44,23 +240,55 @@ impl App {
         let scanner = Scanner::new(&notes_dir);
         let graph = scanner
             .scan()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+            .map_err(|e| io::Error::other(e.to_string()))?;
         let files = scanner
             .list_files()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        // A broken config shouldn't keep the vault from opening
+        let (config, message) = match VaultConfig::load(scanner.root()) {
+            Ok(config) => (config, None),
+            Err(e) => (VaultConfig::default(), Some(e.to_string())),
+        };
 
         let mut app = Self {
             scanner,
             graph,
+            view: Vec::new(),
             files,
+            config,
+            expanded_searches: HashSet::new(),
+            tree_view: true,
+            expanded_dirs: HashSet::new(),
+            query: None,
+            filter: String::new(),
             active_pane: Pane::Files,
             file_list_state: 0,
             backlink_list_state: 0,
             selected_content: String::new(),
+            preview_links: Vec::new(),
+            link_list_state: 0,
+            jump_line: None,
+            history: History::default(),
             backlinks: Vec::new(),
+            mention_list_state: 0,
+            mentions: Vec::new(),
+            mentions_for: None,
+            related_list_state: 0,
+            related: Vec::new(),
+            popup: None,
+            last_search: None,
+            message,
+            pending_edit: None,
+            undo: None,
+            pending_note: None,
+            calendar: None,
+            editor: None,
+            completion: None,
+            completion_dismissed: false,
+            external_edits: Vec::new(),
         };
 
-        app.update_preview();
+        app.rebuild_view();
         Ok(app)
     }
 
@@ -68,21 +296,662 @@ impl App {
         self.graph = self
             .scanner
             .scan()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+            .map_err(|e| io::Error::other(e.to_string()))?;
         self.files = self
             .scanner
             .list_files()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        self.rebuild_view();
+        Ok(())
+    }
+
+    /// Recompute the Files pane from the saved searches, file list and
+    /// active query, keeping the selected row selected where possible.
+    pub fn rebuild_view(&mut self) {
+        let selected = self.view.get(self.file_list_state).cloned();
+        let paths = |indices: Vec<_>| -> Vec<PathBuf> {
+            indices
+                .into_iter()
+                .filter_map(|idx| self.graph.get_note(idx))
+                .map(|note| note.path.clone())
+                .collect()
+        };
+
+        if let Some(calendar) = &mut self.calendar {
+            calendar.refresh(&self.graph);
+        }
+        // Saved searches are re-run on every rebuild so they track the graph
+        let mut view = Vec::new();
+        for (index, search) in self.config.saved_searches.iter().enumerate() {
+            let results = search.run(&self.graph).ok().map(paths);
+            let expanded = self.expanded_searches.contains(&search.name);
+            view.push(FileRow::Saved {
+                index,
+                count: results.as_ref().map(Vec::len),
+                expanded,
+            });
+            if expanded {
+                view.extend(results.into_iter().flatten().map(FileRow::SavedNote));
+            }
+        }
+        let mut notes = match &self.query {
+            Some(query) => query.run(&self.graph),
+            None => self
+                .files
+                .iter()
+                .filter_map(|path| self.graph.find_by_path(path))
+                .collect(),
+        };
+        if !self.filter.is_empty() {
+            let filter = self.filter.to_lowercase();
+            let root = self.scanner.root();
+            notes.retain(|&idx| {
+                self.graph.get_note(idx).is_some_and(|note| {
+                    note.title.to_lowercase().contains(&filter)
+                        || note
+                            .path
+                            .strip_prefix(root)
+                            .unwrap_or(&note.path)
+                            .to_string_lossy()
+                            .to_lowercase()
+                            .contains(&filter)
+                })
+            });
+        }
+        self.config.sort.sort(&self.graph, &mut notes);
+        let notes = paths(notes);
+        if self.tree_view {
+            // Open every folder while a query is active so no match is hidden
+            let expand_all = self.query.is_some();
+            view.extend(tree_rows(
+                self.scanner.root(),
+                &notes,
+                &self.expanded_dirs,
+                expand_all,
+            ));
+        } else {
+            view.extend(
+                notes
+                    .into_iter()
+                    .map(|path| FileRow::Note { path, depth: 0 }),
+            );
+        }
+
+        self.view = view;
+        self.file_list_state = selected
+            .and_then(|row| self.view.iter().position(|r| r.same_entry(&row)))
+            .unwrap_or(self.file_list_state)
+            .min(self.view.len().saturating_sub(1));
         self.update_preview();
+    }
+
+    /// Number of notes in the Files pane, not counting saved searches.
+    pub fn note_count(&self) -> usize {
+        self.view
+            .iter()
+            .filter(|row| matches!(row, FileRow::Note { .. }))
+            .count()
+    }
+
+    /// Open the query prompt, pre-filled with the active query.
+    pub fn open_query_prompt(&mut self) {
+        let current = self
+            .query
+            .as_ref()
+            .map(|q| q.to_string())
+            .unwrap_or_default();
+        self.popup = Some(Popup::Prompt(Prompt {
+            kind: PromptKind::Query,
+            input: Input::with_value(current),
+        }));
+    }
+
+    /// Ask for the title of a new note, starting in the selected folder.
+    pub fn open_new_note_prompt(&mut self) {
+        let folder = match self.view.get(self.file_list_state) {
+            Some(FileRow::Dir { path, .. }) => Some(path.as_path()),
+            Some(row) => row.path().and_then(Path::parent),
+            None => None,
+        };
+        let folder = folder
+            .and_then(|dir| dir.strip_prefix(self.scanner.root()).ok())
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(|dir| format!("{}/", dir.display()))
+            .unwrap_or_default();
+        self.popup = Some(Popup::Prompt(Prompt {
+            kind: PromptKind::NewNote,
+            input: Input::with_value(folder),
+        }));
+    }
+
+    /// Create the note typed into the new-note prompt, first letting the user
+    /// pick a template if the vault has any.
+    fn new_note(&mut self, folder: &Path, title: &str) {
+        match Template::load_all(self.scanner.root()) {
+            Ok(templates) if !templates.is_empty() => {
+                self.popup = Some(Popup::Templates(TemplatePicker {
+                    folder: folder.to_path_buf(),
+                    title: title.trim().to_string(),
+                    templates,
+                    selected: 0,
+                }))
+            }
+            Ok(_) => self.create_note(folder, title, ""),
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Create the note with the template chosen in the picker.
+    pub fn template_selected(&mut self) {
+        let Some(Popup::Templates(picker)) = self.popup.take() else {
+            return;
+        };
+        let Some(template) = picker.selected().cloned() else {
+            self.create_note(&picker.folder, &picker.title, "");
+            return;
+        };
+        self.pending_note = Some(PendingNote {
+            folder: picker.folder,
+            questions: template.prompts(),
+            template,
+            values: TemplateValues::new(picker.title),
+        });
+        self.continue_pending_note();
+    }
+
+    /// Ask the pending note's next template prompt, or create it once all
+    /// are answered.
+    fn continue_pending_note(&mut self) {
+        let Some(pending) = &self.pending_note else {
+            return;
+        };
+        if pending.question().is_some() {
+            self.popup = Some(Popup::Prompt(Prompt {
+                kind: PromptKind::TemplateField,
+                input: Input::default(),
+            }));
+        } else if let Some(pending) = self.pending_note.take() {
+            let content = pending.template.render(&pending.values);
+            self.create_note(&pending.folder, &pending.values.title, &content);
+        }
+    }
+
+    /// Label of the status-bar prompt; template prompts show their question.
+    pub fn prompt_label(&self, prompt: &Prompt) -> String {
+        match (prompt.kind, &self.pending_note) {
+            (PromptKind::TemplateField, Some(pending)) => {
+                format!("{}:", pending.question().unwrap_or_default())
+            }
+            (kind, _) => kind.label().to_string(),
+        }
+    }
+
+    /// Create a note in `folder` (relative to the vault) starting with
+    /// `content`, select it and queue it for editing.
+    fn create_note(&mut self, folder: &Path, title: &str, content: &str) {
+        let path = match self
+            .scanner
+            .create_note_from(&mut self.graph, folder, title, content)
+        {
+            Ok(idx) => match self.graph.get_note(idx) {
+                Some(note) => note.path.clone(),
+                None => return,
+            },
+            Err(e) => {
+                self.message = Some(e.to_string());
+                return;
+            }
+        };
+        self.files_changed();
+        self.navigate_to(&path);
+        self.pending_edit = Some(path);
+    }
+
+    /// Re-list the vault's files after notes were added, moved or removed.
+    fn files_changed(&mut self) {
+        match self.scanner.list_files() {
+            Ok(files) => self.files = files,
+            Err(e) => self.message = Some(e.to_string()),
+        }
+        self.rebuild_view();
+    }
+
+    /// Ask before moving the selected note to the trash.
+    pub fn open_delete_confirm(&mut self) {
+        match self
+            .selected_file()
+            .and_then(|path| DeleteConfirm::new(&self.graph, path))
+        {
+            Some(confirm) => self.popup = Some(Popup::Delete(confirm)),
+            None => self.message = Some("Select a note first".to_string()),
+        }
+    }
+
+    /// Move the note being confirmed to the trash.
+    pub fn delete_confirmed(&mut self) {
+        let Some(Popup::Delete(confirm)) = self.popup.take() else {
+            return;
+        };
+        let Some(idx) = self.graph.find_by_path(&confirm.path) else {
+            return;
+        };
+        match self.scanner.trash_note(&mut self.graph, idx) {
+            Ok(trashed) => {
+                let entry = trashed.entry;
+                self.message = Some(match trashed.errors.first() {
+                    Some((path, e)) => format!(
+                        "Moved {} to the trash, but couldn't re-read {}: {e} · u to undo",
+                        entry.title,
+                        self.file_display_name(path)
+                    ),
+                    None => format!("Moved {} to the trash · u to undo", entry.title),
+                });
+                self.undo = Some(Undo::Trash(entry));
+                self.files_changed();
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// List the notes in the trash.
+    pub fn open_trash(&mut self) {
+        match self.scanner.trash_entries() {
+            Ok(entries) if entries.is_empty() => {
+                self.message = Some("The trash is empty".to_string())
+            }
+            Ok(entries) => {
+                self.popup = Some(Popup::Trash(TrashView {
+                    entries,
+                    selected: 0,
+                }))
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Restore the note selected in the trash and close it.
+    pub fn restore_selected(&mut self) {
+        if let Some(Popup::Trash(view)) = self.popup.take()
+            && let Some(entry) = view.selected()
+        {
+            self.restore(entry);
+        }
+    }
+
+    fn restore(&mut self, entry: &TrashEntry) {
+        match self.scanner.restore_note(&mut self.graph, entry) {
+            Ok(_) => {
+                self.files_changed();
+                self.navigate_to(&self.scanner.root().join(&entry.path));
+                self.message = Some(format!("Restored {}", entry.title));
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Take back the last delete or rename.
+    pub fn undo(&mut self) {
+        match self.undo.take() {
+            None => self.message = Some("Nothing to undo".to_string()),
+            Some(Undo::Trash(entry)) => self.restore(&entry),
+            Some(Undo::Rename {
+                path,
+                old_path,
+                old_title,
+            }) => {
+                let result = self
+                    .graph
+                    .find_by_path(&path)
+                    .ok_or_else(|| Error::NoteNotFound(path.display().to_string()))
+                    .and_then(|idx| {
+                        let plan =
+                            self.scanner
+                                .plan_move(&self.graph, idx, &old_path, &old_title)?;
+                        self.scanner.apply_rename(&mut self.graph, &plan)
+                    });
+                match result {
+                    Ok(_) => {
+                        self.files_changed();
+                        self.navigate_to(&old_path);
+                        self.message = Some(format!("Renamed back to {old_title}"));
+                    }
+                    Err(e) => self.message = Some(e.to_string()),
+                }
+            }
+        }
+    }
+
+    /// Ask for a new title and folder for the selected note.
+    pub fn open_rename_prompt(&mut self) {
+        let Some(note) = self
+            .selected_file()
+            .and_then(|path| self.graph.find_by_path(&path))
+            .and_then(|idx| self.graph.get_note(idx))
+        else {
+            self.message = Some("Select a note first".to_string());
+            return;
+        };
+        let folder = note
+            .path
+            .parent()
+            .and_then(|dir| dir.strip_prefix(self.scanner.root()).ok())
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(|dir| format!("{}/", dir.display()))
+            .unwrap_or_default();
+        self.popup = Some(Popup::Prompt(Prompt {
+            kind: PromptKind::Rename,
+            input: Input::with_value(format!("{folder}{}", note.title)),
+        }));
+    }
+
+    /// Work out what renaming the selected note changes and show it for
+    /// confirmation.
+    fn plan_rename(&mut self, folder: &Path, title: &str) {
+        let Some(idx) = self
+            .selected_file()
+            .and_then(|path| self.graph.find_by_path(&path))
+        else {
+            return;
+        };
+        match self.scanner.plan_rename(&self.graph, idx, folder, title) {
+            Ok(plan) if plan.is_empty() => self.message = Some("Nothing to change".to_string()),
+            Ok(plan) => {
+                self.popup = Some(Popup::Rename(RenamePreview::new(plan, self.scanner.root())));
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Apply the rename shown in the preview and close it.
+    pub fn apply_rename(&mut self) {
+        let Some(Popup::Rename(preview)) = self.popup.take() else {
+            return;
+        };
+        let plan = preview.plan;
+        if let Err(e) = self.scanner.apply_rename(&mut self.graph, &plan) {
+            self.message = Some(e.to_string());
+            return;
+        }
+        self.files_changed();
+        self.navigate_to(&plan.to);
+        self.undo = Some(Undo::Rename {
+            path: plan.to.clone(),
+            old_path: plan.from.clone(),
+            old_title: plan.old_title.clone(),
+        });
+        let links = plan.link_count();
+        let files = plan.files.iter().filter(|f| f.path != plan.from).count();
+        self.message = Some(format!(
+            "Renamed to {} · updated {links} link{} in {files} file{}",
+            plan.new_title,
+            if links == 1 { "" } else { "s" },
+            if files == 1 { "" } else { "s" },
+        ));
+    }
+
+    /// Ask for a name to save the active query under.
+    pub fn open_save_search_prompt(&mut self) {
+        if self.query.is_none() {
+            self.message = Some("No active query to save".to_string());
+            return;
+        }
+        self.popup = Some(Popup::Prompt(Prompt {
+            kind: PromptKind::SaveSearch,
+            input: Input::default(),
+        }));
+    }
+
+    /// Act on the text typed into the status-bar prompt.
+    pub fn submit_prompt(&mut self) {
+        let Some(Popup::Prompt(prompt)) = &self.popup else {
+            return;
+        };
+        let value = prompt.input.value.clone();
+        match prompt.kind {
+            PromptKind::Query => self.submit_query(&value),
+            PromptKind::SaveSearch => self.save_search(&value),
+            PromptKind::Filter => {
+                self.popup = None;
+                self.set_filter(&value);
+            }
+            PromptKind::NewNote => {
+                self.popup = None;
+                let (folder, title) = value.rsplit_once('/').unwrap_or(("", &value));
+                self.new_note(Path::new(folder), title);
+            }
+            PromptKind::Rename => {
+                self.popup = None;
+                let (folder, title) = value.rsplit_once('/').unwrap_or(("", &value));
+                self.plan_rename(Path::new(folder), title);
+            }
+            PromptKind::TemplateField => {
+                self.popup = None;
+                if let Some(pending) = &mut self.pending_note {
+                    pending.answer(value);
+                }
+                self.continue_pending_note();
+            }
+        }
+    }
+
+    /// Cancel the status-bar prompt. Cancelling the filter prompt drops the
+    /// filter, and cancelling a template prompt the note being created.
+    pub fn cancel_prompt(&mut self) {
+        if let Some(Popup::Prompt(prompt)) = self.popup.take() {
+            match prompt.kind {
+                PromptKind::Filter => self.set_filter(""),
+                PromptKind::TemplateField => self.pending_note = None,
+                _ => {}
+            }
+        }
+    }
+
+    /// Open the filter prompt, pre-filled with the current filter.
+    pub fn open_filter_prompt(&mut self) {
+        self.popup = Some(Popup::Prompt(Prompt {
+            kind: PromptKind::Filter,
+            input: Input::with_value(self.filter.clone()),
+        }));
+    }
+
+    /// Narrow the Files pane to notes whose title or path contains `filter`.
+    pub fn set_filter(&mut self, filter: &str) {
+        if self.filter != filter {
+            self.filter = filter.to_string();
+            self.rebuild_view();
+        }
+    }
+
+    /// Move to the next sort key and remember it in the vault config.
+    pub fn cycle_sort(&mut self) {
+        self.config.sort.key = self.config.sort.key.next();
+        self.sort_changed();
+    }
+
+    /// Flip between ascending and descending order.
+    pub fn reverse_sort(&mut self) {
+        self.config.sort.descending = !self.config.sort.descending;
+        self.sort_changed();
+    }
+
+    fn sort_changed(&mut self) {
+        self.rebuild_view();
+        self.message = Some(match self.config.save(self.scanner.root()) {
+            Ok(()) => format!("Sorted by {}", self.sort_label()),
+            Err(e) => e.to_string(),
+        });
+    }
+
+    /// The sort key with an arrow for its direction, e.g. `modified ↓`.
+    pub fn sort_label(&self) -> String {
+        let arrow = if self.config.sort.descending {
+            "↓"
+        } else {
+            "↑"
+        };
+        format!("{} {arrow}", self.config.sort.key)
+    }
+
+    /// Run a query; an empty query clears it.
+    fn submit_query(&mut self, value: &str) {
+        if value.trim().is_empty() {
+            self.popup = None;
+            self.clear_query();
+            return;
+        }
+        match Query::parse(value) {
+            Ok(query) => {
+                self.popup = None;
+                self.query = Some(query);
+                self.rebuild_view();
+                self.active_pane = Pane::Files;
+                self.message = Some(format!("{} matching notes", self.note_count()));
+            }
+            // Keep the prompt open so the query can be fixed
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Save the active query as a named search in the vault config.
+    fn save_search(&mut self, name: &str) {
+        let name = name.trim();
+        let Some(query) = self.query.as_ref().map(|q| q.to_string()) else {
+            self.popup = None;
+            return;
+        };
+        if name.is_empty() {
+            self.message = Some("Name the saved search".to_string());
+            return;
+        }
+        let result = self
+            .config
+            .save_search(name, &query)
+            .and_then(|()| self.config.save(self.scanner.root()));
+        self.popup = None;
+        match result {
+            Ok(()) => {
+                self.message = Some(format!("Saved search {name}"));
+                self.rebuild_view();
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Remove the saved search under the cursor from the vault config.
+    pub fn remove_selected_search(&mut self) {
+        if self.active_pane != Pane::Files {
+            return;
+        }
+        let Some(&FileRow::Saved { index, .. }) = self.view.get(self.file_list_state) else {
+            return;
+        };
+        let name = self.config.saved_searches[index].name.clone();
+        self.config.remove_search(&name);
+        self.expanded_searches.remove(&name);
+        match self.config.save(self.scanner.root()) {
+            Ok(()) => self.message = Some(format!("Removed saved search {name}")),
+            Err(e) => self.message = Some(e.to_string()),
+        }
+        self.rebuild_view();
+    }
+
+    /// Drop the active query and filter and show all files again.
+    pub fn clear_query(&mut self) {
+        let had_filter = !std::mem::take(&mut self.filter).is_empty();
+        if self.query.take().is_some() || had_filter {
+            self.rebuild_view();
+        }
+    }
+
+    /// Line (1-based) to open the previewed note at in an editor: the
+    /// selected link when in the preview pane, else the last jump.
+    pub fn edit_line(&self) -> Option<usize> {
+        if self.active_pane == Pane::Preview
+            && let Some(link) = self.preview_links.get(self.link_list_state)
+        {
+            let before = self.selected_content.get(..link.span.start)?;
+            return Some(before.matches('\n').count() + 1);
+        }
+        self.jump_line
+    }
+
+    /// Keep an eye on a note opened in a GUI editor, to re-read it on save.
+    pub fn watch_edit(&mut self, path: PathBuf, child: Child) {
+        self.external_edits.retain(|edit| edit.path != path);
+        self.external_edits.push(ExternalEdit::new(path, child));
+    }
+
+    /// Re-read notes saved in GUI editors since the last check, and stop
+    /// watching those whose editor has exited.
+    pub fn check_external_edits(&mut self) {
+        // Look for exits first so a save made just before quitting is seen
+        for edit in &mut self.external_edits {
+            edit.reap();
+        }
+        let saved: Vec<PathBuf> = self
+            .external_edits
+            .iter_mut()
+            .filter_map(|edit| edit.saved().then(|| edit.path.clone()))
+            .collect();
+        self.external_edits.retain(ExternalEdit::is_running);
+        for path in saved {
+            self.message = Some(match self.reload_file(&path) {
+                Ok(()) => format!("Reloaded {}", self.file_display_name(&path)),
+                Err(e) => e.to_string(),
+            });
+        }
+    }
+
+    /// Re-read a single note after it was edited, updating the graph in place.
+    pub fn reload_file(&mut self, path: &Path) -> io::Result<()> {
+        self.scanner
+            .update_file(&mut self.graph, path)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        self.graph.update_ranking();
+        self.rebuild_view();
         Ok(())
     }
 
     pub fn next_pane(&mut self) {
         self.active_pane = self.active_pane.next();
+        if self.active_pane == Pane::Calendar && self.calendar.is_none() {
+            self.active_pane = self.active_pane.next();
+        }
+        if self.active_pane == Pane::Mentions {
+            self.load_mentions();
+        }
     }
 
     pub fn prev_pane(&mut self) {
         self.active_pane = self.active_pane.prev();
+        if self.active_pane == Pane::Calendar && self.calendar.is_none() {
+            self.active_pane = self.active_pane.prev();
+        }
+        if self.active_pane == Pane::Mentions {
+            self.load_mentions();
+        }
+    }
+
+    /// Look for unlinked mentions of the selected note, unless already done.
+    fn load_mentions(&mut self) {
+        let Some(path) = self.selected_file() else {
+            return;
+        };
+        if self.mentions_for.as_ref() == Some(&path) {
+            return;
+        }
+        let Some(idx) = self.graph.find_by_path(&path) else {
+            return;
+        };
+        match unlinked_mentions(&self.graph, idx) {
+            Ok(mentions) => self.mentions = mentions,
+            Err(e) => {
+                self.mentions.clear();
+                self.message = Some(format!("Couldn't look for mentions: {e}"));
+            }
+        }
+        self.mentions_for = Some(path);
+        self.mention_list_state = 0;
     }
 
     pub fn move_up(&mut self) {
@@ -98,14 +967,29 @@ impl App {
                     self.backlink_list_state -= 1;
                 }
             }
-            Pane::Preview => {}
+            Pane::Related => {
+                if self.related_list_state > 0 {
+                    self.related_list_state -= 1;
+                }
+            }
+            Pane::Mentions => {
+                if self.mention_list_state > 0 {
+                    self.mention_list_state -= 1;
+                }
+            }
+            Pane::Preview => {
+                if self.link_list_state > 0 {
+                    self.link_list_state -= 1;
+                }
+            }
+            Pane::Calendar => self.calendar_move(-7),
         }
     }
 
     pub fn move_down(&mut self) {
         match self.active_pane {
             Pane::Files => {
-                if self.file_list_state < self.files.len().saturating_sub(1) {
+                if self.file_list_state < self.view.len().saturating_sub(1) {
                     self.file_list_state += 1;
                     self.update_preview();
                 }
@@ -115,59 +999,829 @@ impl App {
                     self.backlink_list_state += 1;
                 }
             }
-            Pane::Preview => {}
+            Pane::Related => {
+                if self.related_list_state < self.related.len().saturating_sub(1) {
+                    self.related_list_state += 1;
+                }
+            }
+            Pane::Mentions => {
+                if self.mention_list_state < self.mentions.len().saturating_sub(1) {
+                    self.mention_list_state += 1;
+                }
+            }
+            Pane::Preview => {
+                if self.link_list_state < self.preview_links.len().saturating_sub(1) {
+                    self.link_list_state += 1;
+                }
+            }
+            Pane::Calendar => self.calendar_move(7),
         }
     }
 
     pub fn select(&mut self) {
         match self.active_pane {
+            Pane::Calendar => {
+                if let Some(calendar) = &self.calendar {
+                    let day = PeriodicNote::new(Period::Day, calendar.cursor);
+                    self.open_periodic_note(day, true);
+                }
+            }
             Pane::Files => {
-                self.update_preview();
+                if self.selected_file().is_some() {
+                    self.update_preview();
+                } else {
+                    self.set_folder_expanded(None);
+                }
             }
             Pane::Backlinks => {
-                if let Some(backlink_title) = self.backlinks.get(self.backlink_list_state) {
-                    // Find the file with this title and navigate to it
-                    if let Some(idx) = self.graph.find_by_title(backlink_title) {
-                        if let Some(note) = self.graph.get_note(idx) {
-                            if let Some(pos) = self.files.iter().position(|f| f == &note.path) {
-                                self.file_list_state = pos;
-                                self.active_pane = Pane::Files;
-                                self.update_preview();
-                            }
+                // Navigate to the linking note
+                if let Some(path) = self
+                    .backlinks
+                    .get(self.backlink_list_state)
+                    .map(|b| b.path.clone())
+                {
+                    self.navigate_to(&path);
+                }
+            }
+            Pane::Related => {
+                if let Some(path) = self
+                    .related
+                    .get(self.related_list_state)
+                    .map(|r| r.path.clone())
+                {
+                    self.navigate_to(&path);
+                }
+            }
+            Pane::Mentions => {
+                // Navigate to the mentioning note
+                if let Some(path) = self
+                    .mentions
+                    .get(self.mention_list_state)
+                    .map(|m| m.path.clone())
+                {
+                    self.navigate_to(&path);
+                }
+            }
+            Pane::Preview => {
+                // Follow the selected wikilink
+                let Some(link) = self.preview_links.get(self.link_list_state) else {
+                    return;
+                };
+                let target = self
+                    .graph
+                    .find_by_title(&link.target)
+                    .and_then(|idx| self.graph.get_note(idx))
+                    .map(|note| note.path.clone());
+                let heading = link.heading.clone();
+                match target {
+                    Some(path) => {
+                        self.navigate_to(&path);
+                        if let Some(heading) = heading {
+                            self.jump_line = heading_line(&self.selected_content, &heading);
                         }
                     }
+                    None => {
+                        // Dated links have their own place in the journal
+                        if let Some(note) = PeriodicNote::parse(&link.target) {
+                            self.open_periodic_note(note, true);
+                            return;
+                        }
+                        // Create the missing note next to the one linking to it
+                        let title = link.target.clone();
+                        let folder = self
+                            .selected_file()
+                            .and_then(|path| {
+                                let dir = path.parent()?.strip_prefix(self.scanner.root()).ok()?;
+                                Some(dir.to_path_buf())
+                            })
+                            .unwrap_or_default();
+                        self.create_note(&folder, &title, "");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Switch the Files pane between the folder tree and a flat list.
+    pub fn toggle_tree_view(&mut self) {
+        self.tree_view = !self.tree_view;
+        self.rebuild_view();
+        self.message = Some(
+            if self.tree_view {
+                "Tree view"
+            } else {
+                "Flat view"
+            }
+            .to_string(),
+        );
+    }
+
+    /// Expand the folder or saved search under the cursor.
+    pub fn expand_selected(&mut self) {
+        self.set_folder_expanded(Some(true));
+    }
+
+    /// Collapse the folder under the cursor, or the one containing the
+    /// selected note.
+    pub fn collapse_selected(&mut self) {
+        self.set_folder_expanded(Some(false));
+    }
+
+    /// Expand, collapse or (with `None`) toggle the selected folder.
+    fn set_folder_expanded(&mut self, expand: Option<bool>) {
+        let Some(row) = self.view.get(self.file_list_state).cloned() else {
+            return;
+        };
+        let row = match row {
+            // Collapsing from inside a folder collapses it and moves up to it
+            FileRow::Note { path, depth } if expand == Some(false) && depth > 0 => {
+                let parent = path.parent().map(Path::to_path_buf);
+                let Some(pos) = self.view.iter().rposition(
+                    |r| matches!(r, FileRow::Dir { path, .. } if Some(path) == parent.as_ref()),
+                ) else {
+                    return;
+                };
+                self.file_list_state = pos;
+                self.view[pos].clone()
+            }
+            FileRow::SavedNote(_) if expand == Some(false) => {
+                let Some(pos) = self.view[..self.file_list_state]
+                    .iter()
+                    .rposition(|r| matches!(r, FileRow::Saved { .. }))
+                else {
+                    return;
+                };
+                self.file_list_state = pos;
+                self.view[pos].clone()
+            }
+            row => row,
+        };
+        match row {
+            FileRow::Saved {
+                index, expanded, ..
+            } => {
+                let name = self.config.saved_searches[index].name.clone();
+                if expand.unwrap_or(!expanded) {
+                    self.expanded_searches.insert(name);
+                } else {
+                    self.expanded_searches.remove(&name);
+                }
+            }
+            FileRow::Dir { path, expanded, .. } => {
+                if expand.unwrap_or(!expanded) {
+                    self.expanded_dirs.insert(path);
+                } else {
+                    self.expanded_dirs.remove(&path);
+                }
+            }
+            _ => return,
+        }
+        self.rebuild_view();
+    }
+
+    /// Jump to a note, recording the current one in the navigation history.
+    pub fn navigate_to(&mut self, path: &Path) {
+        let current = self.selected_file();
+        if self.open(path)
+            && let Some(current) = current
+            && current != path
+        {
+            self.history.visit(current);
+        }
+    }
+
+    /// Go back to the previously visited note.
+    pub fn go_back(&mut self) {
+        let (Some(current), Some(prev)) = (
+            self.selected_file(),
+            self.history.peek_back().map(Path::to_path_buf),
+        ) else {
+            return;
+        };
+        // History only moves once the note is open, so a failure loses nothing
+        if self.open(&prev) {
+            self.history.back(&current);
+        } else {
+            self.message = Some(format!(
+                "{} no longer exists",
+                self.file_display_name(&prev)
+            ));
+        }
+    }
+
+    /// Go forward again after going back.
+    pub fn go_forward(&mut self) {
+        let (Some(current), Some(next)) = (
+            self.selected_file(),
+            self.history.peek_forward().map(Path::to_path_buf),
+        ) else {
+            return;
+        };
+        if self.open(&next) {
+            self.history.forward(&current);
+        } else {
+            self.message = Some(format!(
+                "{} no longer exists",
+                self.file_display_name(&next)
+            ));
+        }
+    }
+
+    /// Select a note in the file list without touching history.
+    /// Clears the active query if it hides the note.
+    fn open(&mut self, path: &Path) -> bool {
+        let is_note = |row: &FileRow| matches!(row, FileRow::Note { path: p, .. } if p == path);
+        if self.query.is_some() && !self.view.iter().any(is_note) {
+            self.clear_query();
+        }
+        // Expand the folders the note is in
+        if !self.view.iter().any(is_note) && self.files.iter().any(|f| f == path) {
+            let root = self.scanner.root();
+            for dir in path.ancestors().skip(1) {
+                if dir == root || !dir.starts_with(root) {
+                    break;
+                }
+                self.expanded_dirs.insert(dir.to_path_buf());
+            }
+            self.rebuild_view();
+        }
+        match self.view.iter().position(is_note) {
+            Some(pos) => {
+                self.file_list_state = pos;
+                self.active_pane = Pane::Files;
+                self.update_preview();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Titles of the recently visited notes followed by the current one.
+    pub fn breadcrumbs(&self, n: usize) -> Vec<String> {
+        self.history
+            .recent(n)
+            .iter()
+            .cloned()
+            .chain(self.selected_file())
+            .map(|path| {
+                self.graph
+                    .find_by_path(&path)
+                    .and_then(|idx| self.graph.get_note(idx))
+                    .map(|note| note.title.clone())
+                    .unwrap_or_else(|| self.file_display_name(&path))
+            })
+            .collect()
+    }
+
+    /// Open the fuzzy note finder.
+    pub fn open_finder(&mut self) {
+        self.popup = Some(Popup::Finder(Finder::new(
+            &self.graph,
+            self.scanner.root(),
+            FinderAction::Open,
+        )));
+    }
+
+    /// Ask for a second note and show how the selected note links to it.
+    pub fn open_chain_finder(&mut self) {
+        let Some(path) = self.selected_file() else {
+            self.message = Some("Select a note first".to_string());
+            return;
+        };
+        self.popup = Some(Popup::Finder(Finder::new(
+            &self.graph,
+            self.scanner.root(),
+            FinderAction::ChainFrom(path),
+        )));
+    }
+
+    /// Act on the note picked in the finder and close it.
+    pub fn finder_select(&mut self) {
+        let Some(Popup::Finder(finder)) = self.popup.take() else {
+            return;
+        };
+        let Some(found) = finder.selected() else {
+            return;
+        };
+        match &finder.action {
+            FinderAction::Open => self.navigate_to(&found.path),
+            FinderAction::ChainFrom(from) => {
+                if let Some(from) = self.graph.find_by_path(from)
+                    && let Some(to) = self.graph.find_by_path(&found.path)
+                {
+                    self.popup = Some(Popup::Chain(LinkChain::new(&self.graph, from, to)));
+                }
+            }
+        }
+    }
+
+    /// Open the note selected in the link chain and close it.
+    pub fn chain_select(&mut self) {
+        if let Some(Popup::Chain(chain)) = self.popup.take()
+            && let Some(path) = chain.selected_path()
+        {
+            self.navigate_to(path);
+        }
+    }
+
+    /// Open the note for the current day, week or month, creating it from
+    /// its template and editing it if it doesn't exist yet.
+    pub fn open_periodic(&mut self, period: Period) {
+        self.open_periodic_note(PeriodicNote::today(period), true);
+    }
+
+    /// Move from the selected daily, weekly or monthly note to the next or
+    /// previous one. Missing notes are created but not opened in $EDITOR.
+    pub fn step_periodic(&mut self, forward: bool) {
+        let current = self
+            .selected_file()
+            .and_then(|path| self.graph.find_by_path(&path))
+            .and_then(|idx| self.graph.get_note(idx))
+            .and_then(|note| PeriodicNote::parse(&note.title));
+        let Some(current) = current else {
+            self.message = Some("Not a daily, weekly or monthly note".to_string());
+            return;
+        };
+        let note = if forward {
+            current.next()
+        } else {
+            current.prev()
+        };
+        self.open_periodic_note(note, false);
+    }
+
+    fn open_periodic_note(&mut self, note: PeriodicNote, edit_new: bool) {
+        let existing = self
+            .graph
+            .find_periodic(note)
+            .and_then(|idx| self.graph.get_note(idx))
+            .map(|note| note.path.clone());
+        if let Some(path) = existing {
+            self.navigate_to(&path);
+            return;
+        }
+        match self.scanner.periodic_note(&mut self.graph, note) {
+            Ok(idx) => {
+                let Some(path) = self.graph.get_note(idx).map(|note| note.path.clone()) else {
+                    return;
+                };
+                self.files_changed();
+                self.navigate_to(&path);
+                if edit_new {
+                    self.pending_edit = Some(path);
+                } else {
+                    self.message = Some(format!("Created {}", note.title()));
+                }
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Edit the selected note in the built-in editor, starting at the line
+    /// last jumped to.
+    pub fn open_builtin_editor(&mut self) {
+        let Some(path) = self.selected_file() else {
+            self.message = Some("Select a note first".to_string());
+            return;
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let mut editor = NoteEditor::new(path, content);
+                if let Some(line) = self.edit_line() {
+                    editor.row = line.saturating_sub(1).min(editor.lines.len() - 1);
+                }
+                self.editor = Some(editor);
+                self.active_pane = Pane::Preview;
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+    }
+
+    /// Pass a key to the built-in editor and carry out what it asks for.
+    pub fn editor_key(&mut self, key: KeyEvent) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+        match editor.handle_key(key) {
+            EditorAction::None => {}
+            EditorAction::Save { force } => {
+                self.save_editor(force);
+            }
+            EditorAction::Close { force } => self.close_editor(force),
+            EditorAction::SaveAndClose => {
+                if self.save_editor(false) {
+                    self.close_editor(false);
+                }
+            }
+            EditorAction::Reload => match fs::read_to_string(&editor.path) {
+                Ok(content) => editor.reload(content),
+                Err(e) => self.message = Some(e.to_string()),
+            },
+            EditorAction::Unknown(command) => {
+                self.message = Some(format!("Not an editor command: {command}"))
+            }
+        }
+    }
+
+    /// Write the editor's buffer and re-index the note. Unless `force`d,
+    /// refuses when the file changed on disk since it was opened.
+    fn save_editor(&mut self, force: bool) -> bool {
+        let Some(editor) = &mut self.editor else {
+            return false;
+        };
+        let content = editor.content();
+        let original = if force {
+            fs::read_to_string(&editor.path).unwrap_or_default()
+        } else {
+            editor.original.clone()
+        };
+        match self
+            .scanner
+            .save_note(&mut self.graph, &editor.path, &original, &content)
+        {
+            Ok(_) => {
+                editor.original = content;
+                let path = editor.path.clone();
+                self.message = Some(format!("Saved {}", self.file_display_name(&path)));
+            }
+            Err(Error::EditConflict(_)) => {
+                self.message =
+                    Some("Changed on disk since opened · :w! to overwrite, :e! to reload".into());
+                return false;
+            }
+            Err(e) => {
+                self.message = Some(e.to_string());
+                return false;
+            }
+        }
+        self.rebuild_view();
+        self.update_preview();
+        true
+    }
+
+    /// Close the built-in editor. Unless `force`d, refuses while there are
+    /// unsaved changes.
+    fn close_editor(&mut self, force: bool) {
+        if !force && self.editor.as_ref().is_some_and(NoteEditor::is_modified) {
+            self.message = Some("Unsaved changes · :w to save, :q! to discard".to_string());
+            return;
+        }
+        if let Some(editor) = self.editor.take() {
+            self.jump_line = Some(editor.row + 1);
+        }
+    }
+
+    /// The text being typed into and the cursor's byte offset in it: the
+    /// open prompt, or the editor's line in insert mode.
+    fn typing(&self) -> Option<(&str, usize)> {
+        match (&self.popup, &self.editor) {
+            (Some(Popup::Prompt(prompt)), _) => Some((&prompt.input.value, prompt.input.cursor)),
+            (None, Some(editor)) if editor.mode == Mode::Insert => Some(editor.cursor_line()),
+            _ => None,
+        }
+    }
+
+    /// Suggest link targets if a `[[link` is being typed.
+    pub fn update_completion(&mut self) {
+        let completion = self
+            .typing()
+            .and_then(|(text, cursor)| LinkCompletion::new(&self.graph, text, cursor));
+        if completion.is_none() {
+            self.completion_dismissed = false;
+        }
+        self.completion = completion.filter(|_| !self.completion_dismissed);
+    }
+
+    /// Handle a key meant for the suggestions popup. Returns false for keys
+    /// that should go to the text being typed.
+    pub fn completion_key(&mut self, key: KeyEvent) -> bool {
+        let Some(completion) = &mut self.completion else {
+            return false;
+        };
+        match (key.code, key.modifiers) {
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => completion.move_up(),
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                completion.move_down()
+            }
+            (KeyCode::Esc, _) => {
+                self.completion = None;
+                self.completion_dismissed = true;
+            }
+            (KeyCode::Enter | KeyCode::Tab, _) => self.accept_completion(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Put the selected suggestion in place of the link being typed.
+    fn accept_completion(&mut self) {
+        let Some(completion) = self.completion.take() else {
+            return;
+        };
+        match (&mut self.popup, &mut self.editor) {
+            (Some(Popup::Prompt(prompt)), _) => {
+                prompt.input.cursor =
+                    completion.apply(&mut prompt.input.value, prompt.input.cursor);
+            }
+            (None, Some(editor)) => editor.edit_line(|line, cursor| completion.apply(line, cursor)),
+            _ => {}
+        }
+    }
+
+    /// Show or hide the calendar pane, focusing it when shown.
+    pub fn toggle_calendar(&mut self) {
+        if self.calendar.take().is_some() {
+            if self.active_pane == Pane::Calendar {
+                self.active_pane = Pane::Files;
+            }
+        } else {
+            self.calendar = Some(Calendar::new(&self.graph));
+            self.active_pane = Pane::Calendar;
+        }
+    }
+
+    /// Move the calendar's selected day.
+    pub fn calendar_move(&mut self, days: i64) {
+        if let Some(calendar) = &mut self.calendar {
+            calendar.move_days(&self.graph, days);
+        }
+    }
+
+    /// Move the calendar to the same day in the next or previous month.
+    pub fn calendar_month(&mut self, forward: bool) {
+        if let Some(calendar) = &mut self.calendar {
+            calendar.move_months(&self.graph, forward);
+        }
+    }
+
+    /// List the notes touched on the calendar's selected day.
+    pub fn open_day_view(&mut self) {
+        let Some(calendar) = &self.calendar else {
+            return;
+        };
+        match calendar.selected() {
+            Some(activity) => {
+                let view = DayView::new(&self.graph, calendar.cursor, activity);
+                self.popup = Some(Popup::Day(view));
+            }
+            None => {
+                self.message = Some(format!("Nothing on {}", calendar.cursor.format("%Y-%m-%d")))
+            }
+        }
+    }
+
+    /// Open the note selected in the day list.
+    pub fn day_view_select(&mut self) {
+        if let Some(Popup::Day(view)) = self.popup.take()
+            && let Some(row) = view.rows.get(view.selected)
+        {
+            self.navigate_to(&row.path);
+        }
+    }
+
+    /// Draw the notes around the selected one.
+    pub fn open_local_graph(&mut self) {
+        let Some(idx) = self
+            .selected_file()
+            .and_then(|path| self.graph.find_by_path(&path))
+        else {
+            self.message = Some("Select a note first".to_string());
+            return;
+        };
+        self.popup = Some(Popup::Graph(LocalGraph::new(&self.graph, idx)));
+    }
+
+    /// Open the note selected in the local graph and close it.
+    pub fn local_graph_select(&mut self) {
+        if let Some(Popup::Graph(view)) = self.popup.take()
+            && let Some(path) = view.selected_path()
+        {
+            self.navigate_to(path);
+        }
+    }
+
+    /// Open the vault health report.
+    pub fn open_health(&mut self) {
+        self.popup = Some(Popup::Health(HealthView::new(&self.graph)));
+    }
+
+    /// Open the note picked in the health report and close it.
+    pub fn health_select(&mut self) {
+        if let Some(Popup::Health(view)) = self.popup.take()
+            && let Some(path) = view.selected_path()
+        {
+            self.navigate_to(path);
+        }
+    }
+
+    /// Open the full-text search panel, restoring the previous search.
+    pub fn open_search(&mut self) {
+        let panel = self.last_search.take().unwrap_or_default();
+        self.popup = Some(Popup::Search(panel));
+    }
+
+    /// Close the search panel, keeping its state for later.
+    pub fn close_search(&mut self) {
+        if let Some(Popup::Search(panel)) = self.popup.take() {
+            self.last_search = Some(panel);
+        }
+    }
+
+    /// Jump to the match selected in the search panel.
+    pub fn search_select(&mut self) {
+        self.close_search();
+        self.jump_to_search_match();
+    }
+
+    /// Jump to the next (or previous) match of the last search.
+    pub fn search_step(&mut self, forward: bool) {
+        match self.last_search.as_mut() {
+            Some(panel) if !panel.entries.is_empty() => {
+                if forward {
+                    panel.next();
+                } else {
+                    panel.prev();
                 }
+                self.jump_to_search_match();
             }
-            Pane::Preview => {}
+            _ => self.message = Some("No search results".to_string()),
         }
     }
 
+    fn jump_to_search_match(&mut self) {
+        let Some((path, line)) = self
+            .last_search
+            .as_ref()
+            .and_then(|panel| panel.selected())
+            .map(|(path, snippet)| (path.to_path_buf(), snippet.line))
+        else {
+            return;
+        };
+        self.navigate_to(&path);
+        self.jump_line = Some(line);
+    }
+
+    /// Expand or collapse the occurrences of the selected backlink.
+    pub fn toggle_backlink(&mut self) {
+        if self.active_pane == Pane::Backlinks
+            && let Some(backlink) = self.backlinks.get_mut(self.backlink_list_state)
+        {
+            backlink.expanded = !backlink.expanded;
+        }
+    }
+
+    /// Rewrite the selected unlinked mention into a wikilink to the current note.
+    pub fn link_selected_mention(&mut self) -> io::Result<()> {
+        if self.active_pane != Pane::Mentions {
+            return Ok(());
+        }
+        let Some(mention) = self.mentions.get(self.mention_list_state).cloned() else {
+            return Ok(());
+        };
+        let Some(title) = self
+            .selected_file()
+            .and_then(|path| self.graph.find_by_path(&path))
+            .and_then(|idx| self.graph.get_note(idx))
+            .map(|note| note.title.clone())
+        else {
+            return Ok(());
+        };
+
+        match link_mention(&mention.path, mention.span.clone(), &mention.text, &title) {
+            Ok(()) => {
+                self.message = Some(format!(
+                    "Linked mention in {}",
+                    self.file_display_name(&mention.path)
+                ));
+                let selected = self.mention_list_state;
+                self.refresh()?;
+                self.mention_list_state = selected.min(self.mentions.len().saturating_sub(1));
+            }
+            Err(e) => self.message = Some(e.to_string()),
+        }
+        Ok(())
+    }
+
     pub fn selected_file(&self) -> Option<PathBuf> {
-        self.files.get(self.file_list_state).cloned()
+        self.view
+            .get(self.file_list_state)
+            .and_then(FileRow::path)
+            .map(Path::to_path_buf)
     }
 
     fn update_preview(&mut self) {
         if let Some(path) = self.selected_file() {
             self.selected_content = fs::read_to_string(&path).unwrap_or_else(|_| String::new());
+            self.preview_links = extract_wikilink_spans(&self.selected_content);
+            self.link_list_state = 0;
+            self.jump_line = None;
 
             // Update backlinks
             self.backlinks.clear();
             if let Some(idx) = self.graph.find_by_path(&path) {
-                for note in self.graph.backlinks(idx) {
-                    self.backlinks.push(note.title.clone());
+                for backlink in self.graph.backlink_occurrences(idx) {
+                    let source = fs::read_to_string(&backlink.note.path).unwrap_or_default();
+                    let snippets = backlink
+                        .spans
+                        .iter()
+                        // Spans are from the last scan; skip any the file no longer has
+                        .filter(|span| source.get((*span).clone()).is_some())
+                        .map(|span| Snippet::at(&source, span.clone()))
+                        .collect();
+                    self.backlinks.push(BacklinkEntry {
+                        title: backlink.note.title.clone(),
+                        path: backlink.note.path.clone(),
+                        snippets,
+                        expanded: false,
+                    });
                 }
             }
             self.backlink_list_state = 0;
+
+            self.mentions.clear();
+            self.mentions_for = None;
+            self.mention_list_state = 0;
+            if self.active_pane == Pane::Mentions {
+                self.load_mentions();
+            }
+
+            self.related = self
+                .graph
+                .find_by_path(&path)
+                .map(|idx| self.graph.related(idx, RELATED_LIMIT))
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|related| {
+                    let note = self.graph.get_note(related.note)?;
+                    Some(RelatedEntry {
+                        title: note.title.clone(),
+                        path: note.path.clone(),
+                        reason: related_reason(&related),
+                    })
+                })
+                .collect();
+            self.related_list_state = 0;
         } else {
-            self.selected_content.clear();
+            self.selected_content = match self.view.get(self.file_list_state) {
+                Some(&FileRow::Saved { index, count, .. }) => {
+                    let search = &self.config.saved_searches[index];
+                    let summary = match count {
+                        Some(n) => format!("{n} matching notes"),
+                        None => "This query no longer parses.".to_string(),
+                    };
+                    format!(
+                        "Saved search: {}\n\nQuery: {}\n\n{summary}",
+                        search.name, search.query
+                    )
+                }
+                _ => String::new(),
+            };
+            self.preview_links.clear();
             self.backlinks.clear();
+            self.mentions.clear();
+            self.mentions_for = None;
+            self.related.clear();
         }
     }
 
-    pub fn file_display_name(&self, path: &PathBuf) -> String {
+    pub fn file_display_name(&self, path: &Path) -> String {
         path.strip_prefix(self.scanner.root())
             .unwrap_or(path)
             .display()
             .to_string()
     }
 }
+
+/// Summarise what two related notes have in common.
+fn related_reason(related: &Related) -> String {
+    let mut parts = Vec::new();
+    if !related.cocited_by.is_empty() {
+        parts.push(format!("cited together {}×", related.cocited_by.len()));
+    }
+    if !related.shared_links.is_empty() {
+        let n = related.shared_links.len();
+        parts.push(format!("{n} shared link{}", if n == 1 { "" } else { "s" }));
+    }
+    parts.extend(related.shared_tags.iter().map(|tag| format!("#{tag}")));
+    parts.extend(
+        related
+            .shared_unresolved
+            .iter()
+            .map(|target| format!("both want [[{target}]]")),
+    );
+    parts.join(" · ")
+}
+
+/// Line (1-based) of the heading with the given text, ignoring case.
+fn heading_line(content: &str, heading: &str) -> Option<usize> {
+    content
+        .lines()
+        .position(|line| {
+            line.starts_with('#')
+                && line
+                    .trim_start_matches('#')
+                    .trim()
+                    .eq_ignore_ascii_case(heading.trim())
+        })
+        .map(|i| i + 1)
+}
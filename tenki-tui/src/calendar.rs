@@ -0,0 +1,145 @@
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tenki_core::{DayActivity, NoteGraph};
+
+/// State of the calendar pane: a month grid around the selected day.
+#[derive(Debug)]
+pub struct Calendar {
+    pub cursor: NaiveDate,
+    /// Activity for the days of the cursor's month.
+    pub activity: BTreeMap<NaiveDate, DayActivity>,
+}
+
+impl Calendar {
+    pub fn new(graph: &NoteGraph) -> Self {
+        let mut calendar = Self {
+            cursor: Local::now().date_naive(),
+            activity: BTreeMap::new(),
+        };
+        calendar.refresh(graph);
+        calendar
+    }
+
+    fn month_start(&self) -> NaiveDate {
+        self.cursor.with_day(1).unwrap_or(self.cursor)
+    }
+
+    /// Re-read the activity of the shown month, e.g. after notes changed.
+    pub fn refresh(&mut self, graph: &NoteGraph) {
+        let start = self.month_start();
+        let end = start + Months::new(1) - Days::new(1);
+        self.activity = graph.activity(start, end);
+    }
+
+    /// Select another day, re-reading activity if the month changes.
+    pub fn set_cursor(&mut self, graph: &NoteGraph, date: NaiveDate) {
+        let month = self.month_start();
+        self.cursor = date;
+        if self.month_start() != month {
+            self.refresh(graph);
+        }
+    }
+
+    pub fn move_days(&mut self, graph: &NoteGraph, days: i64) {
+        if let Some(date) = self.cursor.checked_add_signed(chrono::Duration::days(days)) {
+            self.set_cursor(graph, date);
+        }
+    }
+
+    pub fn move_months(&mut self, graph: &NoteGraph, forward: bool) {
+        let date = if forward {
+            self.cursor.checked_add_months(Months::new(1))
+        } else {
+            self.cursor.checked_sub_months(Months::new(1))
+        };
+        if let Some(date) = date {
+            self.set_cursor(graph, date);
+        }
+    }
+
+    /// The month as rows of Monday-to-Sunday weeks, `None` outside the month.
+    pub fn weeks(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let start = self.month_start();
+        let mut weeks = Vec::new();
+        let mut week = [None; 7];
+        let mut date = start;
+        while date.month() == start.month() {
+            let weekday = date.weekday().num_days_from_monday() as usize;
+            week[weekday] = Some(date);
+            if weekday == 6 {
+                weeks.push(week);
+                week = [None; 7];
+            }
+            date = date + Days::new(1);
+        }
+        if week.iter().any(Option::is_some) {
+            weeks.push(week);
+        }
+        weeks
+    }
+
+    /// Activity on the selected day.
+    pub fn selected(&self) -> Option<&DayActivity> {
+        self.activity.get(&self.cursor)
+    }
+}
+
+/// A note in the list of notes touched on a day.
+#[derive(Debug)]
+pub struct DayRow {
+    pub path: PathBuf,
+    pub title: String,
+    /// Why the note is listed, e.g. `created` or `due: Pay rent`.
+    pub reason: String,
+}
+
+/// State of the popup listing what happened on a calendar day.
+#[derive(Debug)]
+pub struct DayView {
+    pub date: NaiveDate,
+    pub rows: Vec<DayRow>,
+    pub selected: usize,
+}
+
+impl DayView {
+    pub fn new(graph: &NoteGraph, date: NaiveDate, activity: &DayActivity) -> Self {
+        let mut rows = Vec::new();
+        let mut push = |idx, reason: String| {
+            if let Some(note) = graph.get_note(idx) {
+                rows.push(DayRow {
+                    path: note.path.clone(),
+                    title: note.title.clone(),
+                    reason,
+                });
+            }
+        };
+        if let Some(idx) = activity.journal {
+            push(idx, "daily note".to_string());
+        }
+        for &idx in &activity.created {
+            push(idx, "created".to_string());
+        }
+        for &idx in &activity.modified {
+            push(idx, "modified".to_string());
+        }
+        for (idx, task) in &activity.due {
+            push(*idx, format!("due: {}", task.text));
+        }
+        Self {
+            date,
+            rows,
+            selected: 0,
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.rows.len() {
+            self.selected += 1;
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+use tenki_core::{NodeIndex, NoteGraph};
+
+/// How a step in a link chain relates to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrow {
+    /// The previous note links to this one.
+    Forward,
+    /// This note links to the previous one.
+    Backward,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChainStep {
+    pub path: PathBuf,
+    pub title: String,
+    /// `None` for the first note.
+    pub arrow: Option<Arrow>,
+}
+
+/// State of the popup showing how two notes connect.
+#[derive(Debug)]
+pub struct LinkChain {
+    pub from: NodeIndex,
+    pub to: NodeIndex,
+    /// Only follow links in the direction they point.
+    pub directed: bool,
+    /// Empty when the notes aren't connected.
+    pub steps: Vec<ChainStep>,
+    pub selected: usize,
+}
+
+impl LinkChain {
+    /// Find the chain from `from` to `to`, following links backwards too if
+    /// there is no chain of outgoing links.
+    pub fn new(graph: &NoteGraph, from: NodeIndex, to: NodeIndex) -> Self {
+        let mut chain = Self {
+            from,
+            to,
+            directed: true,
+            steps: Vec::new(),
+            selected: 0,
+        };
+        chain.update(graph);
+        if chain.steps.is_empty() {
+            chain.directed = false;
+            chain.update(graph);
+        }
+        chain
+    }
+
+    /// Switch between directed and undirected chains.
+    pub fn toggle_directed(&mut self, graph: &NoteGraph) {
+        self.directed = !self.directed;
+        self.update(graph);
+    }
+
+    fn update(&mut self, graph: &NoteGraph) {
+        let path = if self.directed {
+            graph.shortest_path(self.from, self.to)
+        } else {
+            graph.shortest_path_undirected(self.from, self.to)
+        };
+        let path = path.unwrap_or_default();
+        self.steps = path
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &idx)| {
+                let note = graph.get_note(idx)?;
+                let arrow = i.checked_sub(1).map(|prev| {
+                    if graph.has_link(path[prev], idx) {
+                        Arrow::Forward
+                    } else {
+                        Arrow::Backward
+                    }
+                });
+                Some(ChainStep {
+                    path: note.path.clone(),
+                    title: note.title.clone(),
+                    arrow,
+                })
+            })
+            .collect();
+        self.selected = 0;
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected < self.steps.len().saturating_sub(1) {
+            self.selected += 1;
+        }
+    }
+
+    pub fn selected_path(&self) -> Option<&Path> {
+        self.steps
+            .get(self.selected)
+            .map(|step| step.path.as_path())
+    }
+}
@@ -0,0 +1,976 @@
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tenki_core::{
+    ExportFormat, ExportScope, HealthReport, NodeIndex, Note, NoteGraph, Query, Scanner, Snippet,
+    Template, TemplateValues, VaultConfig, extract_wikilink_spans,
+};
+
+const USAGE: &str = "\
+Usage: tenki [DIR | --dir DIR]        start the TUI on a vault
+       tenki [OPTIONS] <COMMAND> [ARGS] [OPTIONS]
+
+Commands:
+  list [QUERY]              notes, optionally matching a query such as `tag:#project backlinks>=3`
+  show NOTE                 a note's contents
+  backlinks NOTE            lines in other notes linking to NOTE
+  links NOTE                wikilinks in NOTE and where they lead
+  broken                    links to notes that don't exist (exits with an error if any)
+  orphans                   notes with no links in or out
+  search QUERY              full-text search (--limit N for the N best matches)
+  saved [NAME]              saved searches, or the notes matching the one named
+  tags                      tags and how many notes have each
+  graph                     export the link graph (--format dot|graphml|json|mermaid,
+                            --tag TAG, or --around NOTE with --depth N)
+  new TITLE                 create a note (--folder DIR, --template NAME)
+  rename NOTE TITLE         rename a note and update links to it (--folder DIR, --dry-run)
+  health                    orphans, dead ends, hubs and cycles
+
+NOTE is a title, alias or path relative to the vault.
+
+Options may come before or after the command:
+  --dir DIR                 the vault (default: current directory)
+  --json                    print JSON instead of text
+";
+
+/// Options that take a value.
+const VALUE_OPTIONS: &[&str] = &[
+    "--dir",
+    "--format",
+    "--tag",
+    "--around",
+    "--depth",
+    "--folder",
+    "--template",
+    "--limit",
+];
+
+type Command = fn(&Args, &mut dyn Write) -> Result<()>;
+
+fn command(name: &str) -> Option<Command> {
+    Some(match name {
+        "help" | "--help" | "-h" => help,
+        "list" => list,
+        "show" => show,
+        "backlinks" => backlinks,
+        "links" => links,
+        "broken" => broken,
+        "orphans" => orphans,
+        "search" => search,
+        "saved" => saved,
+        "tags" => tags,
+        "graph" => graph,
+        "new" => new,
+        "rename" => rename,
+        "health" => health,
+        _ => return None,
+    })
+}
+
+/// Run a subcommand if `args` names one, or return `None` to start the TUI.
+/// The command is the first word that isn't an option or its value, so
+/// `tenki --dir list` opens a vault called `list`.
+pub fn run(args: &[String]) -> Option<Result<()>> {
+    run_with(args, &mut io::stdout().lock())
+}
+
+fn run_with(args: &[String], out: &mut dyn Write) -> Option<Result<()>> {
+    let position = first_word(args)?;
+    let run = command(&args[position])?;
+    let rest: Vec<String> = args[..position]
+        .iter()
+        .chain(&args[position + 1..])
+        .cloned()
+        .collect();
+    Some(
+        Args::parse(&rest)
+            .and_then(|args| run(&args, out))
+            .or_else(ignore_broken_pipe),
+    )
+}
+
+/// The vault to open in the TUI: `--dir DIR` or a bare `DIR`.
+pub fn vault_dir(args: &[String]) -> Result<Option<PathBuf>> {
+    let args = Args::parse(args)?;
+    Ok(args
+        .option("--dir")
+        .or(args.words.first().map(String::as_str))
+        .map(PathBuf::from))
+}
+
+/// Index of the first positional argument, skipping options and their values.
+fn first_word(args: &[String]) -> Option<usize> {
+    let mut i = 0;
+    while let Some(arg) = args.get(i) {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            i += 2;
+        } else if arg.starts_with("--") && arg != "--help" {
+            i += 1;
+        } else {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Stop quietly when the output is closed early, e.g. piped into `head`.
+fn ignore_broken_pipe(error: anyhow::Error) -> Result<()> {
+    match error.downcast_ref::<io::Error>() {
+        Some(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        _ => Err(error),
+    }
+}
+
+fn help(_: &Args, out: &mut dyn Write) -> Result<()> {
+    write!(out, "{USAGE}")?;
+    Ok(())
+}
+
+/// What follows a subcommand: positional words and `--options`.
+struct Args {
+    words: Vec<String>,
+    options: BTreeMap<String, String>,
+    json: bool,
+    dry_run: bool,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self> {
+        let mut parsed = Self {
+            words: Vec::new(),
+            options: BTreeMap::new(),
+            json: false,
+            dry_run: false,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => parsed.json = true,
+                "--dry-run" => parsed.dry_run = true,
+                option if VALUE_OPTIONS.contains(&option) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("{option} needs a value"))?;
+                    parsed.options.insert(option.to_string(), value.clone());
+                }
+                option if option.starts_with("--") => {
+                    bail!("Unknown option {option}, see `tenki help`")
+                }
+                word => parsed.words.push(word.to_string()),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    /// The positional argument at `i`, described as `name` if missing.
+    fn word(&self, i: usize, name: &str) -> Result<&str> {
+        self.words
+            .get(i)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("Missing {name}, see `tenki help`"))
+    }
+
+    /// The vault directory, from `--dir` or the current directory.
+    fn root(&self) -> PathBuf {
+        PathBuf::from(self.option("--dir").unwrap_or("."))
+    }
+
+    /// Scan the vault.
+    fn vault(&self) -> Result<Vault> {
+        let scanner = Scanner::new(self.root());
+        let graph = scanner.scan()?;
+        Ok(Vault { scanner, graph })
+    }
+}
+
+/// A scanned vault.
+struct Vault {
+    scanner: Scanner,
+    graph: NoteGraph,
+}
+
+impl Vault {
+    fn root(&self) -> &Path {
+        self.scanner.root()
+    }
+
+    fn note(&self, idx: NodeIndex) -> Option<&Note> {
+        self.graph.get_note(idx)
+    }
+
+    /// A path relative to the vault, with `/` separators.
+    fn relative(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(self.root()).unwrap_or(path);
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Find a note by title, alias or path relative to the vault (`.md`
+    /// optional).
+    fn find(&self, name: &str) -> Result<NodeIndex> {
+        let path = self.root().join(name);
+        self.graph
+            .find_by_title(name.trim())
+            .or_else(|| self.graph.find_by_path(&path))
+            .or_else(|| self.graph.find_by_path(&path.with_extension("md")))
+            .ok_or_else(|| anyhow!("No note named '{name}'"))
+    }
+
+    fn summary(&self, idx: NodeIndex) -> Option<NoteSummary> {
+        let note = self.note(idx)?;
+        Some(NoteSummary {
+            path: self.relative(&note.path),
+            title: note.title.clone(),
+            aliases: note.aliases.clone(),
+            tags: note.meta.tags.clone(),
+        })
+    }
+
+    /// Print notes as `path<TAB>title` lines, or as JSON.
+    fn print_notes(
+        &self,
+        out: &mut dyn Write,
+        notes: impl IntoIterator<Item = NodeIndex>,
+        json: bool,
+    ) -> Result<()> {
+        let notes: Vec<NoteSummary> = notes
+            .into_iter()
+            .filter_map(|idx| self.summary(idx))
+            .collect();
+        if json {
+            return print_json(out, &notes);
+        }
+        for note in notes {
+            writeln!(out, "{}\t{}", note.path, note.title)?;
+        }
+        Ok(())
+    }
+}
+
+fn print_json(out: &mut dyn Write, value: &impl Serialize) -> Result<()> {
+    writeln!(out, "{}", serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct NoteSummary {
+    path: String,
+    title: String,
+    aliases: Vec<String>,
+    tags: Vec<String>,
+}
+
+/// A line of a note, e.g. a link or search match.
+#[derive(Serialize)]
+struct LineRef {
+    path: String,
+    line: usize,
+    text: String,
+}
+
+/// List notes, all of them or those matching a query.
+fn list(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let notes = if args.words.is_empty() {
+        let mut notes: Vec<NodeIndex> = vault.graph.note_indices().collect();
+        notes.sort_by_cached_key(|&idx| vault.note(idx).map(|note| vault.relative(&note.path)));
+        notes
+    } else {
+        Query::parse(&args.words.join(" "))?.run(&vault.graph)
+    };
+    vault.print_notes(out, notes, args.json)
+}
+
+#[derive(Serialize)]
+struct NoteDetails {
+    #[serde(flatten)]
+    note: NoteSummary,
+    words: usize,
+    links: Vec<String>,
+    backlinks: Vec<String>,
+    content: String,
+}
+
+/// Print a note's contents.
+fn show(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let idx = vault.find(args.word(0, "NOTE")?)?;
+    let note = vault.note(idx).context("note vanished")?;
+    let content = fs::read_to_string(&note.path)?;
+    if !args.json {
+        write!(out, "{content}")?;
+        return Ok(());
+    }
+    let paths = |notes: Vec<&Note>| {
+        let mut paths: Vec<String> = notes.iter().map(|n| vault.relative(&n.path)).collect();
+        paths.sort();
+        paths
+    };
+    print_json(
+        out,
+        &NoteDetails {
+            note: vault.summary(idx).context("note vanished")?,
+            words: note.meta.words,
+            links: paths(vault.graph.forward_links(idx)),
+            backlinks: paths(vault.graph.backlinks(idx)),
+            content,
+        },
+    )
+}
+
+/// Print each line linking to a note, as `path:line<TAB>text`.
+fn backlinks(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let idx = vault.find(args.word(0, "NOTE")?)?;
+    let mut lines: Vec<LineRef> = Vec::new();
+    for backlink in vault.graph.backlink_occurrences(idx) {
+        let content = fs::read_to_string(&backlink.note.path)?;
+        let path = vault.relative(&backlink.note.path);
+        for span in backlink.spans {
+            let snippet = Snippet::at(&content, span.clone());
+            lines.push(LineRef {
+                path: path.clone(),
+                line: snippet.line,
+                text: snippet.text,
+            });
+        }
+    }
+    lines.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    lines.dedup_by(|b, a| a.path == b.path && a.line == b.line);
+    if args.json {
+        return print_json(out, &lines);
+    }
+    for line in lines {
+        writeln!(out, "{}:{}\t{}", line.path, line.line, line.text)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct LinkOut {
+    line: usize,
+    target: String,
+    heading: Option<String>,
+    /// The note linked to, or `None` if there is none.
+    path: Option<String>,
+}
+
+/// Print the wikilinks in a note, as `line<TAB>target<TAB>path`.
+fn links(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let idx = vault.find(args.word(0, "NOTE")?)?;
+    let note = vault.note(idx).context("note vanished")?;
+    let content = fs::read_to_string(&note.path)?;
+    let links: Vec<LinkOut> = extract_wikilink_spans(&content)
+        .into_iter()
+        .map(|link| LinkOut {
+            line: Snippet::at(&content, link.span.clone()).line,
+            path: vault
+                .graph
+                .find_by_title(&link.target)
+                .and_then(|idx| vault.note(idx))
+                .map(|note| vault.relative(&note.path)),
+            target: link.target,
+            heading: link.heading,
+        })
+        .collect();
+    if args.json {
+        return print_json(out, &links);
+    }
+    for link in links {
+        let target = match &link.heading {
+            Some(heading) => format!("{}#{heading}", link.target),
+            None => link.target.clone(),
+        };
+        let path = link.path.as_deref().unwrap_or("(missing)");
+        writeln!(out, "{}\t{target}\t{path}", link.line)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct BrokenLink {
+    path: String,
+    line: usize,
+    target: String,
+}
+
+/// Print links to missing notes, failing if there are any so CI can catch
+/// them. Links to journal dates don't count: those notes are made on demand.
+fn broken(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let mut contents: BTreeMap<NodeIndex, String> = BTreeMap::new();
+    let mut links: Vec<BrokenLink> = Vec::new();
+    for (idx, link) in vault.graph.broken_links() {
+        let Some(note) = vault.note(idx) else {
+            continue;
+        };
+        let content = contents
+            .entry(idx)
+            .or_insert_with(|| fs::read_to_string(&note.path).unwrap_or_default());
+        links.push(BrokenLink {
+            path: vault.relative(&note.path),
+            line: Snippet::at(content, link.span.clone()).line,
+            target: link.target.clone(),
+        });
+    }
+    links.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    if args.json {
+        print_json(out, &links)?;
+    } else {
+        for link in &links {
+            writeln!(out, "{}:{}\t[[{}]]", link.path, link.line, link.target)?;
+        }
+    }
+    match links.len() {
+        0 => Ok(()),
+        1 => bail!("1 broken link"),
+        n => bail!("{n} broken links"),
+    }
+}
+
+/// Print notes with no links in or out.
+fn orphans(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    vault.print_notes(out, vault.graph.orphans(), args.json)
+}
+
+#[derive(Serialize)]
+struct SearchResult {
+    path: String,
+    title: String,
+    score: f64,
+    lines: Vec<LineRef>,
+}
+
+/// Full-text search, printing matching lines as `path:line<TAB>text`.
+fn search(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let query = args.words.join(" ");
+    if query.trim().is_empty() {
+        bail!("Missing QUERY, see `tenki help`");
+    }
+    let limit = match args.option("--limit") {
+        Some(limit) => limit.parse().context("--limit needs a number")?,
+        None => usize::MAX,
+    };
+    let results: Vec<SearchResult> = vault
+        .graph
+        .search_index()
+        .search(&query)?
+        .into_iter()
+        .take(limit)
+        .map(|hit| {
+            let path = vault.relative(&hit.path);
+            SearchResult {
+                title: vault
+                    .graph
+                    .find_by_path(&hit.path)
+                    .and_then(|idx| vault.note(idx))
+                    .map(|note| note.title.clone())
+                    .unwrap_or_default(),
+                score: hit.score,
+                lines: hit
+                    .lines
+                    .into_iter()
+                    .map(|snippet| LineRef {
+                        path: path.clone(),
+                        line: snippet.line,
+                        text: snippet.text,
+                    })
+                    .collect(),
+                path,
+            }
+        })
+        .collect();
+    if args.json {
+        return print_json(out, &results);
+    }
+    for line in results.iter().flat_map(|result| &result.lines) {
+        writeln!(out, "{}:{}\t{}", line.path, line.line, line.text)?;
+    }
+    Ok(())
+}
+
+/// List saved searches as `name<TAB>query`, or run the one named.
+fn saved(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let config = VaultConfig::load(vault.root())?;
+    if args.words.is_empty() {
+        if args.json {
+            return print_json(out, &config.saved_searches);
+        }
+        for search in &config.saved_searches {
+            writeln!(out, "{}\t{}", search.name, search.query)?;
+        }
+        return Ok(());
+    }
+    let name = args.words.join(" ");
+    let search = config
+        .saved_search(&name)
+        .ok_or_else(|| anyhow!("No saved search named '{name}'"))?;
+    vault.print_notes(out, search.run(&vault.graph)?, args.json)
+}
+
+#[derive(Serialize)]
+struct TagCount {
+    tag: String,
+    count: usize,
+}
+
+/// Print tags with how many notes have each, most used first.
+fn tags(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    // Tags differing only in case are the same tag
+    let mut counts: BTreeMap<String, TagCount> = BTreeMap::new();
+    for note in vault.graph.all_notes() {
+        for tag in &note.meta.tags {
+            counts
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| TagCount {
+                    tag: tag.clone(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+    let mut tags: Vec<TagCount> = counts.into_values().collect();
+    tags.sort_by_key(|tag| std::cmp::Reverse(tag.count));
+    if args.json {
+        return print_json(out, &tags);
+    }
+    for tag in tags {
+        writeln!(out, "{}\t#{}", tag.count, tag.tag)?;
+    }
+    Ok(())
+}
+
+/// Export the whole graph, a tag's notes, or the neighbourhood of a note.
+fn graph(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let format = match args.option("--format") {
+        Some(format) => format.parse()?,
+        None if args.json => ExportFormat::Json,
+        None => ExportFormat::Dot,
+    };
+    let scope = match (args.option("--tag"), args.option("--around")) {
+        (Some(_), Some(_)) => bail!("Use either --tag or --around"),
+        (Some(tag), None) => ExportScope::Tag(tag.to_string()),
+        (None, Some(note)) => ExportScope::Neighborhood {
+            center: vault.find(note)?,
+            depth: match args.option("--depth") {
+                Some(depth) => depth.parse().context("--depth needs a number")?,
+                None => 2,
+            },
+        },
+        (None, None) => ExportScope::All,
+    };
+    write!(out, "{}", vault.graph.export(format, &scope, vault.root()))?;
+    Ok(())
+}
+
+/// Create a note, from a template if one is named, and print its path.
+fn new(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let mut vault = args.vault()?;
+    let title = args.word(0, "TITLE")?;
+    let folder = PathBuf::from(args.option("--folder").unwrap_or(""));
+    let content = match args.option("--template") {
+        // Prompts can't be asked here, so they are left blank
+        Some(name) => Template::find(vault.root(), name)?
+            .ok_or_else(|| anyhow!("No template named '{name}'"))?
+            .render(&TemplateValues::new(title)),
+        None => String::new(),
+    };
+    let idx = vault
+        .scanner
+        .create_note_from(&mut vault.graph, &folder, title, &content)?;
+    if args.json {
+        return print_json(out, &vault.summary(idx));
+    }
+    if let Some(note) = vault.note(idx) {
+        writeln!(out, "{}", vault.relative(&note.path))?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct RenameSummary {
+    from: String,
+    to: String,
+    old_title: String,
+    new_title: String,
+    /// Links rewritten in other notes.
+    links: usize,
+    /// Files written, including the renamed note.
+    files: Vec<String>,
+    applied: bool,
+}
+
+/// Rename a note, or with `--dry-run` show what would change.
+fn rename(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let mut vault = args.vault()?;
+    let idx = vault.find(args.word(0, "NOTE")?)?;
+    let title = args.word(1, "TITLE")?;
+    let folder = match args.option("--folder") {
+        Some(folder) => PathBuf::from(folder),
+        None => {
+            let note = vault.note(idx).context("note vanished")?;
+            let dir = note.path.parent().unwrap_or(vault.root());
+            dir.strip_prefix(vault.root()).unwrap_or(dir).to_path_buf()
+        }
+    };
+    let plan = vault
+        .scanner
+        .plan_rename(&vault.graph, idx, &folder, title)?;
+    if plan.is_empty() {
+        bail!("Nothing to change");
+    }
+    if !args.dry_run {
+        vault.scanner.apply_rename(&mut vault.graph, &plan)?;
+    }
+    let summary = RenameSummary {
+        from: vault.relative(&plan.from),
+        to: vault.relative(&plan.to),
+        old_title: plan.old_title.clone(),
+        new_title: plan.new_title.clone(),
+        links: plan.link_count(),
+        files: plan.files.iter().map(|f| vault.relative(&f.path)).collect(),
+        applied: !args.dry_run,
+    };
+    if args.json {
+        return print_json(out, &summary);
+    }
+    if args.dry_run {
+        for file in &plan.files {
+            for change in file.changed_lines() {
+                writeln!(out, "{}:{}", vault.relative(&file.path), change.line)?;
+                writeln!(out, "  - {}", change.before)?;
+                writeln!(out, "  + {}", change.after)?;
+            }
+        }
+    }
+    let verb = if args.dry_run {
+        "Would rename"
+    } else {
+        "Renamed"
+    };
+    writeln!(
+        out,
+        "{verb} {} to {}, updating {} link{} in other notes",
+        summary.from,
+        summary.to,
+        summary.links,
+        if summary.links == 1 { "" } else { "s" }
+    )?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct HealthJson {
+    notes: usize,
+    links: usize,
+    orphans: Vec<String>,
+    dead_ends: Vec<String>,
+    hubs: Vec<(String, usize)>,
+    cycles: Vec<Vec<String>>,
+}
+
+/// Print orphans, dead ends, hubs and cycles.
+fn health(args: &Args, out: &mut dyn Write) -> Result<()> {
+    let vault = args.vault()?;
+    let graph = &vault.graph;
+    let report = HealthReport::new(graph);
+
+    if args.json {
+        let paths = |notes: &[NodeIndex]| -> Vec<String> {
+            notes
+                .iter()
+                .filter_map(|&idx| vault.note(idx))
+                .map(|note| vault.relative(&note.path))
+                .collect()
+        };
+        return print_json(
+            out,
+            &HealthJson {
+                notes: report.notes,
+                links: report.links,
+                orphans: paths(&report.orphans),
+                dead_ends: paths(&report.dead_ends),
+                hubs: report
+                    .hubs
+                    .iter()
+                    .filter_map(|&(idx, count)| {
+                        Some((vault.relative(&vault.note(idx)?.path), count))
+                    })
+                    .collect(),
+                cycles: report.components.iter().map(|c| paths(c)).collect(),
+            },
+        );
+    }
+
+    let line = |idx| {
+        graph
+            .get_note(idx)
+            .map(|note| format!("{}  ({})", note.title, vault.relative(&note.path)))
+            .unwrap_or_default()
+    };
+
+    writeln!(out, "{} notes, {} links", report.notes, report.links)?;
+
+    writeln!(out, "\nOrphans ({})", report.orphans.len())?;
+    print_notes(out, &report.orphans, &line)?;
+
+    writeln!(out, "\nDead ends ({})", report.dead_ends.len())?;
+    print_notes(out, &report.dead_ends, &line)?;
+
+    writeln!(out, "\nHubs")?;
+    for &(idx, count) in &report.hubs {
+        writeln!(out, "  {count:>4}  {}", line(idx))?;
+    }
+
+    writeln!(out, "\nCycles ({})", report.components.len())?;
+    for (i, component) in report.components.iter().enumerate() {
+        writeln!(out, "  cycle {}:", i + 1)?;
+        for &idx in component {
+            writeln!(out, "    {}", line(idx))?;
+        }
+    }
+    Ok(())
+}
+
+fn print_notes(
+    out: &mut dyn Write,
+    notes: &[NodeIndex],
+    line: &dyn Fn(NodeIndex) -> String,
+) -> io::Result<()> {
+    if notes.is_empty() {
+        writeln!(out, "  none")?;
+    }
+    for &idx in notes {
+        writeln!(out, "  {}", line(idx))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    /// A copy of `sample_notes`, so commands that write leave it alone.
+    fn sample_vault() -> TempDir {
+        fn copy(from: &Path, to: &Path) {
+            fs::create_dir_all(to).unwrap();
+            for entry in fs::read_dir(from).unwrap() {
+                let path = entry.unwrap().path();
+                let target = to.join(path.file_name().unwrap());
+                if path.is_dir() {
+                    copy(&path, &target);
+                } else {
+                    fs::copy(&path, &target).unwrap();
+                }
+            }
+        }
+        let temp_dir = TempDir::new().unwrap();
+        let samples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../sample_notes");
+        copy(&samples, temp_dir.path());
+        temp_dir
+    }
+
+    /// Run a command on the vault with `--json` and parse what it printed.
+    fn run_json(vault: &TempDir, args: &[&str]) -> (Result<()>, Value) {
+        let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        args.extend(["--json".into(), "--dir".into()]);
+        args.push(vault.path().display().to_string());
+        let mut out = Vec::new();
+        let result = run_with(&args, &mut out).expect("not a command");
+        let value = serde_json::from_slice(&out).unwrap_or(Value::Null);
+        (result, value)
+    }
+
+    fn json(vault: &TempDir, args: &[&str]) -> Value {
+        let (result, value) = run_json(vault, args);
+        result.unwrap();
+        value
+    }
+
+    fn keys(value: &Value) -> Vec<&str> {
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .expect("an object")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_first_word() {
+        let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(first_word(&args(&["list", "--dir", "x"])), Some(0));
+        assert_eq!(
+            first_word(&args(&["--dir", "list", "--json", "tags"])),
+            Some(3)
+        );
+        assert_eq!(first_word(&args(&["--dir", "list"])), None);
+        assert_eq!(first_word(&args(&["--help"])), Some(0));
+        assert!(run_with(&args(&["--dir", "list"]), &mut Vec::new()).is_none());
+        assert!(run_with(&args(&["notes"]), &mut Vec::new()).is_none());
+        assert_eq!(
+            vault_dir(&args(&["--dir", "list"])).unwrap(),
+            Some(PathBuf::from("list"))
+        );
+    }
+
+    #[test]
+    fn test_note_commands() {
+        let vault = sample_vault();
+
+        let notes = json(&vault, &["list"]);
+        let notes = notes.as_array().unwrap();
+        assert_eq!(notes.len(), 4);
+        assert_eq!(keys(&notes[0]), ["aliases", "path", "tags", "title"]);
+        assert_eq!(notes[0]["path"], "keyboard_shortcuts.md");
+        assert_eq!(
+            json(&vault, &["list", "title:wiki"])[0]["title"],
+            "Wikilinks"
+        );
+
+        let note = json(&vault, &["show", "Wikilinks"]);
+        assert_eq!(
+            keys(&note),
+            [
+                "aliases",
+                "backlinks",
+                "content",
+                "links",
+                "path",
+                "tags",
+                "title",
+                "words"
+            ]
+        );
+        assert_eq!(note["path"], "wikilinks.md");
+        assert_eq!(
+            note["backlinks"],
+            json!(["markdown_syntax.md", "welcome.md"])
+        );
+        assert!(note["content"].as_str().unwrap().starts_with("# Wikilinks"));
+
+        let backlinks = json(&vault, &["backlinks", "wikilinks.md"]);
+        assert_eq!(
+            backlinks[1],
+            json!({"path": "welcome.md", "line": 9, "text": "- [[Wikilinks]]"})
+        );
+
+        let links = json(&vault, &["links", "Wikilinks"]);
+        assert_eq!(keys(&links[0]), ["heading", "line", "path", "target"]);
+        assert_eq!(links[0]["path"], Value::Null);
+        assert!(
+            links
+                .as_array()
+                .unwrap()
+                .contains(&json!({"line": 13, "target": "Welcome to Tenki", "heading": null, "path": "welcome.md"}))
+        );
+
+        let (result, broken) = run_json(&vault, &["broken"]);
+        assert_eq!(result.unwrap_err().to_string(), "4 broken links");
+        assert_eq!(
+            broken[0],
+            json!({"path": "keyboard_shortcuts.md", "line": 61, "target": "Note"})
+        );
+
+        assert_eq!(json(&vault, &["orphans"]), json!([]));
+        assert!(run_json(&vault, &["show", "Nowhere"]).0.is_err());
+    }
+
+    #[test]
+    fn test_vault_commands() {
+        let vault = sample_vault();
+
+        let hits = json(&vault, &["search", "wikilinks", "--limit", "2"]);
+        let hits = hits.as_array().unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(keys(&hits[0]), ["lines", "path", "score", "title"]);
+        assert_eq!(keys(&hits[0]["lines"][0]), ["line", "path", "text"]);
+        assert!(run_json(&vault, &["search"]).0.is_err());
+
+        assert_eq!(json(&vault, &["tags"]), json!([]));
+
+        let graph = json(&vault, &["graph"]);
+        assert_eq!(graph["nodes"].as_array().unwrap().len(), 4);
+
+        let health = json(&vault, &["health"]);
+        assert_eq!(
+            keys(&health),
+            ["cycles", "dead_ends", "hubs", "links", "notes", "orphans"]
+        );
+        assert_eq!(health["notes"], 4);
+        assert_eq!(health["hubs"][0], json!(["welcome.md", 3]));
+    }
+
+    #[test]
+    fn test_saved_searches() {
+        let vault = sample_vault();
+        assert_eq!(json(&vault, &["saved"]), json!([]));
+
+        let mut config = VaultConfig::default();
+        config.save_search("Links", "title:link").unwrap();
+        config.save_search("Hubs", "backlinks>=3").unwrap();
+        config.save(vault.path()).unwrap();
+
+        assert_eq!(
+            json(&vault, &["saved"]),
+            json!([
+                {"name": "Links", "query": "title:link"},
+                {"name": "Hubs", "query": "backlinks>=3"},
+            ])
+        );
+        let notes = json(&vault, &["saved", "links"]);
+        assert_eq!(notes.as_array().unwrap().len(), 1);
+        assert_eq!(notes[0]["path"], "wikilinks.md");
+        assert!(run_json(&vault, &["saved", "Nothing"]).0.is_err());
+    }
+
+    #[test]
+    fn test_writing_commands() {
+        let vault = sample_vault();
+
+        let note = json(&vault, &["new", "Ideas", "--folder", "inbox"]);
+        assert_eq!(note["path"], "inbox/ideas.md");
+        assert!(vault.path().join("inbox/ideas.md").exists());
+
+        let plan = json(&vault, &["rename", "Wikilinks", "Links", "--dry-run"]);
+        assert_eq!(
+            plan,
+            json!({
+                "from": "wikilinks.md",
+                "to": "links.md",
+                "old_title": "Wikilinks",
+                "new_title": "Links",
+                "links": 2,
+                "files": ["markdown_syntax.md", "welcome.md", "wikilinks.md"],
+                "applied": false,
+            })
+        );
+        assert!(vault.path().join("wikilinks.md").exists());
+
+        let summary = json(&vault, &["rename", "Wikilinks", "Links"]);
+        assert_eq!(summary["applied"], true);
+        assert!(vault.path().join("links.md").exists());
+        let content = fs::read_to_string(vault.path().join("welcome.md")).unwrap();
+        assert!(content.contains("[[Links]]"));
+    }
+}
@@ -0,0 +1,51 @@
+use tenki_core::{Completion, LinkQuery, NoteGraph};
+
+/// How many suggestions the popup lists.
+const COMPLETION_LIMIT: usize = 8;
+
+/// State of the popup suggesting targets for a `[[link` being typed.
+#[derive(Debug)]
+pub struct LinkCompletion {
+    pub query: LinkQuery,
+    pub items: Vec<Completion>,
+    pub selected: usize,
+}
+
+impl LinkCompletion {
+    /// Suggestions for the link being typed at byte offset `cursor` of
+    /// `text`, or `None` if there is no link or nothing matches.
+    pub fn new(graph: &NoteGraph, text: &str, cursor: usize) -> Option<Self> {
+        let query = LinkQuery::at(text, cursor)?;
+        let items = graph.complete_link(&query, COMPLETION_LIMIT);
+        (!items.is_empty()).then_some(Self {
+            query,
+            items,
+            selected: 0,
+        })
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.items.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Replace the typed link in `text` with the selected suggestion and
+    /// return the cursor after it. A `]]` already after the cursor is reused.
+    pub fn apply(&self, text: &mut String, cursor: usize) -> usize {
+        let Some(item) = self.items.get(self.selected) else {
+            return cursor;
+        };
+        let end = if text[cursor..].starts_with("]]") {
+            cursor + 2
+        } else {
+            cursor
+        };
+        text.replace_range(self.query.start..end, &item.insert);
+        self.query.start + item.insert.len()
+    }
+}
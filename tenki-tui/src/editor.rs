@@ -1,41 +1,395 @@
 use std::env;
+use std::fs;
 use std::io;
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::SystemTime;
 
-/// Opens the given file in the user's preferred editor.
-/// Falls back to common editors if $EDITOR is not set.
-pub fn open_in_editor(path: &Path) -> io::Result<()> {
-    let editor = env::var("EDITOR")
-        .or_else(|_| env::var("VISUAL"))
-        .unwrap_or_else(|_| detect_editor());
+/// Editors that open their own window, so the TUI keeps running beside them.
+const GUI_EDITORS: &[&str] = &[
+    "atom",
+    "code",
+    "code-insiders",
+    "codium",
+    "cursor",
+    "gedit",
+    "gvim",
+    "kate",
+    "mate",
+    "mvim",
+    "subl",
+    "xed",
+    "zed",
+];
 
-    let status = Command::new(&editor).arg(path).status()?;
+/// How an editor is told which line to open at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineArg {
+    /// `+LINE file`, understood by vi and most terminal editors.
+    Plus,
+    /// `--goto file:LINE`, as VS Code and its forks expect.
+    Goto,
+    /// `file:LINE`
+    Suffix,
+    /// Unknown editor: the file alone.
+    None,
+}
+
+/// The user's editor command: a program and the arguments given with it,
+/// e.g. `code -w`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl EditorCommand {
+    /// The editor from `$EDITOR` or `$VISUAL`, or else the first common
+    /// editor found on `PATH`.
+    pub fn from_env() -> Self {
+        ["EDITOR", "VISUAL"]
+            .into_iter()
+            .filter_map(|var| env::var(var).ok())
+            .find_map(|command| Self::parse(&command))
+            .unwrap_or_else(detect_editor)
+    }
+
+    /// Split a command line the way a shell would, e.g. `"code -w"`. Returns
+    /// `None` for an empty command or an unclosed quote.
+    pub fn parse(command: &str) -> Option<Self> {
+        let mut words = split_words(command)?.into_iter();
+        Some(Self {
+            program: words.next()?,
+            args: words.collect(),
+        })
+    }
+
+    /// The program's file name, e.g. `nvim` for `/usr/bin/nvim`.
+    fn name(&self) -> &str {
+        let name = Path::new(&self.program)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.program);
+        name.strip_suffix(".exe").unwrap_or(name)
+    }
 
-    if !status.success() {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("Editor '{}' exited with non-zero status", editor),
-        ));
+    /// Whether the editor runs in its own window rather than the terminal.
+    pub fn is_gui(&self) -> bool {
+        GUI_EDITORS.contains(&self.name())
     }
 
-    Ok(())
+    fn line_arg(&self) -> LineArg {
+        match self.name() {
+            "vi" | "vim" | "nvim" | "gvim" | "mvim" | "view" | "nano" | "pico" | "emacs"
+            | "emacsclient" | "kak" | "micro" | "joe" | "jed" | "mg" | "ne" | "gedit" | "xed" => {
+                LineArg::Plus
+            }
+            "code" | "code-insiders" | "codium" | "cursor" => LineArg::Goto,
+            "subl" | "zed" | "hx" | "helix" => LineArg::Suffix,
+            _ => LineArg::None,
+        }
+    }
+
+    /// The command to open `path`, at the 1-based `line` if the editor
+    /// supports it.
+    fn command(&self, path: &Path, line: Option<usize>) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        match (line, self.line_arg()) {
+            (Some(line), LineArg::Plus) => {
+                command.arg(format!("+{line}")).arg(path);
+            }
+            (Some(line), LineArg::Goto) => {
+                command.arg("--goto").arg(with_line(path, line));
+            }
+            (Some(line), LineArg::Suffix) => {
+                command.arg(with_line(path, line));
+            }
+            (None, _) | (_, LineArg::None) => {
+                command.arg(path);
+            }
+        }
+        command
+    }
+
+    /// Open the file and wait for the editor to exit.
+    pub fn run(&self, path: &Path, line: Option<usize>) -> io::Result<()> {
+        let status = self.command(path, line).status()?;
+
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "Editor '{}' exited with non-zero status",
+                self.program
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Start the editor on the file without waiting for it, detached from
+    /// the terminal.
+    pub fn spawn(&self, path: &Path, line: Option<usize>) -> io::Result<Child> {
+        self.command(path, line)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+    }
+}
+
+/// `path:line`, for editors that take the line that way.
+fn with_line(path: &Path, line: usize) -> String {
+    format!("{}:{line}", path.display())
+}
+
+/// Split `text` into words on unquoted whitespace. Single quotes keep
+/// everything literally, double quotes allow `\"` and `\\`, and a backslash
+/// outside quotes escapes the next character.
+fn split_words(text: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '\\') => word.push(c),
+                            c => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_default().push(chars.next()?),
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    Some(words)
+}
+
+/// Find an executable on `PATH`.
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
 }
 
 /// Try to detect an available editor on the system.
-fn detect_editor() -> String {
+fn detect_editor() -> EditorCommand {
     let editors = ["nvim", "vim", "vi", "nano", "code", "emacs"];
 
-    for editor in editors {
-        if Command::new("which")
-            .arg(editor)
-            .output()
-            .is_ok_and(|o| o.status.success())
+    let program = editors
+        .into_iter()
+        .find(|editor| find_in_path(editor).is_some())
+        // Last resort fallback
+        .unwrap_or("vi");
+    EditorCommand {
+        program: program.to_string(),
+        args: Vec::new(),
+    }
+}
+
+/// A note open in an editor running beside the TUI, watched for saves.
+#[derive(Debug)]
+pub struct ExternalEdit {
+    pub path: PathBuf,
+    modified: Option<SystemTime>,
+    /// The editor process, until it exits.
+    child: Option<Child>,
+}
+
+impl ExternalEdit {
+    pub fn new(path: PathBuf, child: Child) -> Self {
+        Self {
+            modified: modified(&path),
+            path,
+            child: Some(child),
+        }
+    }
+
+    /// Whether the note was written since the last check.
+    pub fn saved(&mut self) -> bool {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        modified.is_some()
+    }
+
+    /// Whether the editor is still running. The note is watched only until
+    /// then, even if it is gone meanwhile: the editor may write it back.
+    pub fn is_running(&self) -> bool {
+        self.child.is_some()
+    }
+
+    /// Whether the editor has exited, collecting it once it has so it
+    /// doesn't linger as a zombie.
+    pub fn reap(&mut self) -> bool {
+        if let Some(child) = &mut self.child
+            && !matches!(child.try_wait(), Ok(None))
         {
-            return editor.to_string();
+            self.child = None;
         }
+        self.child.is_none()
     }
+}
 
-    // Last resort fallback
-    "vi".to_string()
+impl Drop for ExternalEdit {
+    fn drop(&mut self) {
+        // Nobody is watching any more, but the editor still has to be waited for
+        if let Some(mut child) = self.child.take()
+            && matches!(child.try_wait(), Ok(None))
+        {
+            thread::spawn(move || child.wait());
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        let cases: &[(&str, Option<&[&str]>)] = &[
+            ("code -w", Some(&["code", "-w"])),
+            ("  nvim   -u  NONE ", Some(&["nvim", "-u", "NONE"])),
+            ("", Some(&[])),
+            ("'my editor' --flag", Some(&["my editor", "--flag"])),
+            ("a'b c'd", Some(&["ab cd"])),
+            ("''", Some(&[""])),
+            (r#"'it\s "raw"'"#, Some(&[r#"it\s "raw""#])),
+            (
+                r#""C:\\Program Files\\ed" -n"#,
+                Some(&[r"C:\Program Files\ed", "-n"]),
+            ),
+            (r#""say \"hi\"""#, Some(&[r#"say "hi""#])),
+            (r#""keep \n""#, Some(&[r"keep \n"])),
+            (r"path\ with\ spaces", Some(&["path with spaces"])),
+            ("'unterminated", None),
+            ("\"unterminated", None),
+            (r#""escaped end\""#, None),
+            (r"trailing\", None),
+        ];
+        for &(text, expected) in cases {
+            let expected = expected.map(|words| words.iter().map(|w| w.to_string()).collect());
+            assert_eq!(split_words(text), expected, "splitting {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            EditorCommand::parse("'/opt/my ed/bin/ed' -w"),
+            Some(EditorCommand {
+                program: "/opt/my ed/bin/ed".into(),
+                args: vec!["-w".into()],
+            })
+        );
+        assert_eq!(EditorCommand::parse("   "), None);
+        assert_eq!(EditorCommand::parse("vim 'oops"), None);
+    }
+
+    #[test]
+    fn test_line_placement() {
+        let cases: &[(&str, Option<usize>, &[&str])] = &[
+            ("nvim", Some(3), &["+3", "n.md"]),
+            ("emacsclient -t", Some(3), &["-t", "+3", "n.md"]),
+            ("/usr/bin/vi", Some(1), &["+1", "n.md"]),
+            ("code -w", Some(3), &["-w", "--goto", "n.md:3"]),
+            ("subl", Some(3), &["n.md:3"]),
+            ("hx", Some(7), &["n.md:7"]),
+            ("ed", Some(3), &["n.md"]),
+            ("nvim", None, &["n.md"]),
+            ("code -w", None, &["-w", "n.md"]),
+        ];
+        for &(command, line, expected) in cases {
+            let editor = EditorCommand::parse(command).unwrap();
+            let built = editor.command(Path::new("n.md"), line);
+            let args: Vec<&str> = built.get_args().filter_map(|arg| arg.to_str()).collect();
+            assert_eq!(args, expected, "{command} at {line:?}");
+            assert_eq!(built.get_program(), editor.program.as_str());
+        }
+    }
+
+    #[test]
+    fn test_is_gui() {
+        let cases = [
+            ("code -w", true),
+            ("/usr/local/bin/zed", true),
+            ("subl.exe", true),
+            ("gvim -f", true),
+            ("vim", false),
+            ("nano", false),
+            ("codex", false),
+        ];
+        for (command, gui) in cases {
+            assert_eq!(
+                EditorCommand::parse(command).unwrap().is_gui(),
+                gui,
+                "{command}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_edit_waits_for_editor() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("note.md");
+        fs::write(&path, "# Note").unwrap();
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let mut edit = ExternalEdit::new(path.clone(), child);
+
+        fs::remove_file(&path).unwrap();
+        // The editor could still save the note back
+        assert!(!edit.saved());
+        assert!(!edit.reap());
+        assert!(edit.is_running());
+        fs::write(&path, "# Note again").unwrap();
+        assert!(edit.saved());
+
+        edit.child.as_mut().unwrap().kill().unwrap();
+        let start = SystemTime::now();
+        while !edit.reap() {
+            assert!(start.elapsed().unwrap().as_secs() < 10, "editor not reaped");
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(!edit.is_running());
+    }
 }
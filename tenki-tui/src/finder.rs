@@ -0,0 +1,57 @@
+use crate::input::Input;
+use std::path::{Path, PathBuf};
+use tenki_core::{NoteGraph, NoteMatch, find_notes};
+
+/// Maximum number of results kept for display.
+const MAX_RESULTS: usize = 50;
+
+/// What picking a note in the finder does.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FinderAction {
+    /// Open the note.
+    #[default]
+    Open,
+    /// Show the link chain from the given note to the picked one.
+    ChainFrom(PathBuf),
+}
+
+/// State of the quick switcher popup.
+#[derive(Debug, Default)]
+pub struct Finder {
+    pub input: Input,
+    pub results: Vec<NoteMatch>,
+    pub selected: usize,
+    pub action: FinderAction,
+}
+
+impl Finder {
+    pub fn new(graph: &NoteGraph, root: &Path, action: FinderAction) -> Self {
+        let mut finder = Self {
+            action,
+            ..Self::default()
+        };
+        finder.update(graph, root);
+        finder
+    }
+
+    /// Re-rank results after the query changed.
+    pub fn update(&mut self, graph: &NoteGraph, root: &Path) {
+        self.results = find_notes(graph, root, &self.input.value);
+        self.results.truncate(MAX_RESULTS);
+        self.selected = 0;
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected < self.results.len().saturating_sub(1) {
+            self.selected += 1;
+        }
+    }
+
+    pub fn selected(&self) -> Option<&NoteMatch> {
+        self.results.get(self.selected)
+    }
+}
@@ -0,0 +1,140 @@
+use std::path::{Path, PathBuf};
+use tenki_core::{HealthReport, NoteGraph};
+
+/// One line of the vault health view.
+#[derive(Debug)]
+pub enum HealthRow {
+    Heading(String),
+    /// A line of explanation that can't be selected.
+    Info(String),
+    Note {
+        path: PathBuf,
+        title: String,
+        detail: Option<String>,
+    },
+}
+
+/// State of the vault health popup.
+#[derive(Debug, Default)]
+pub struct HealthView {
+    pub summary: String,
+    pub rows: Vec<HealthRow>,
+    /// Index into `rows`; always a `Note` row when there is one.
+    pub selected: usize,
+}
+
+impl HealthView {
+    pub fn new(graph: &NoteGraph) -> Self {
+        let report = HealthReport::new(graph);
+        let note = |idx, detail: Option<String>| {
+            graph.get_note(idx).map(|note| HealthRow::Note {
+                path: note.path.clone(),
+                title: note.title.clone(),
+                detail,
+            })
+        };
+        let mut rows = Vec::new();
+        let section = |rows: &mut Vec<HealthRow>, heading: String, notes: Vec<HealthRow>| {
+            rows.push(HealthRow::Heading(heading));
+            if notes.is_empty() {
+                rows.push(HealthRow::Info("none".to_string()));
+            }
+            rows.extend(notes);
+        };
+
+        section(
+            &mut rows,
+            format!("Orphans ({}) · no links in or out", report.orphans.len()),
+            report
+                .orphans
+                .iter()
+                .filter_map(|&idx| note(idx, None))
+                .collect(),
+        );
+        section(
+            &mut rows,
+            format!(
+                "Dead ends ({}) · linked but link nowhere",
+                report.dead_ends.len()
+            ),
+            report
+                .dead_ends
+                .iter()
+                .filter_map(|&idx| note(idx, None))
+                .collect(),
+        );
+        section(
+            &mut rows,
+            "Hubs · most backlinks".to_string(),
+            report
+                .hubs
+                .iter()
+                .filter_map(|&(idx, count)| {
+                    let noun = if count == 1 { "backlink" } else { "backlinks" };
+                    note(idx, Some(format!("{count} {noun}")))
+                })
+                .collect(),
+        );
+        section(
+            &mut rows,
+            format!(
+                "Cycles ({}) · notes that all link round to each other",
+                report.components.len()
+            ),
+            report
+                .components
+                .iter()
+                .enumerate()
+                .flat_map(|(i, component)| {
+                    let detail = format!("cycle {} of {} notes", i + 1, component.len());
+                    component
+                        .iter()
+                        .filter_map(move |&idx| note(idx, Some(detail.clone())))
+                })
+                .collect(),
+        );
+
+        let mut view = Self {
+            summary: format!("{} notes, {} links", report.notes, report.links),
+            rows,
+            selected: 0,
+        };
+        view.selected = view.step(0, true).unwrap_or(0);
+        view
+    }
+
+    /// The first note row at or after (or before) `from`.
+    fn step(&self, from: usize, forward: bool) -> Option<usize> {
+        let is_note = |i: &usize| matches!(self.rows[*i], HealthRow::Note { .. });
+        if forward {
+            (from..self.rows.len()).find(is_note)
+        } else {
+            (0..=from.min(self.rows.len().saturating_sub(1)))
+                .rev()
+                .find(is_note)
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if let Some(prev) = self
+            .selected
+            .checked_sub(1)
+            .and_then(|i| self.step(i, false))
+        {
+            self.selected = prev;
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if let Some(next) = self.step(self.selected + 1, true) {
+            self.selected = next;
+        }
+    }
+
+    pub fn selected_path(&self) -> Option<&Path> {
+        match self.rows.get(self.selected)? {
+            HealthRow::Note { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
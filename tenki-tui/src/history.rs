@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+/// Maximum number of entries kept in each direction.
+const MAX_HISTORY: usize = 100;
+
+/// Back/forward history of notes visited by following links or jumps.
+#[derive(Debug, Default)]
+pub struct History {
+    back: Vec<PathBuf>,
+    forward: Vec<PathBuf>,
+}
+
+impl History {
+    /// Record that we are leaving `from` for a new note.
+    pub fn visit(&mut self, from: PathBuf) {
+        if self.back.last() != Some(&from) {
+            self.back.push(from);
+        }
+        if self.back.len() > MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+
+    /// The note `back` would return, without stepping.
+    pub fn peek_back(&self) -> Option<&Path> {
+        self.back.last().map(PathBuf::as_path)
+    }
+
+    /// The note `forward` would return, without stepping.
+    pub fn peek_forward(&self) -> Option<&Path> {
+        self.forward.last().map(PathBuf::as_path)
+    }
+
+    /// Step back from `current`, returning the note to show.
+    pub fn back(&mut self, current: &Path) -> Option<PathBuf> {
+        let prev = self.back.pop()?;
+        self.forward.push(current.to_path_buf());
+        Some(prev)
+    }
+
+    /// Step forward from `current`, returning the note to show.
+    pub fn forward(&mut self, current: &Path) -> Option<PathBuf> {
+        let next = self.forward.pop()?;
+        self.back.push(current.to_path_buf());
+        Some(next)
+    }
+
+    /// The most recent `n` notes we came from, oldest first.
+    pub fn recent(&self, n: usize) -> &[PathBuf] {
+        &self.back[self.back.len().saturating_sub(n)..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history() {
+        let (a, b, c) = (Path::new("a.md"), Path::new("b.md"), Path::new("c.md"));
+        let mut history = History::default();
+        history.visit(a.to_path_buf());
+        history.visit(b.to_path_buf());
+
+        // Peeking leaves both stacks alone
+        assert_eq!(history.peek_back(), Some(b));
+        assert_eq!(history.peek_back(), Some(b));
+        assert_eq!(history.peek_forward(), None);
+
+        assert_eq!(history.back(c).as_deref(), Some(b));
+        assert_eq!(history.peek_forward(), Some(c));
+        assert_eq!(history.forward(b).as_deref(), Some(c));
+        assert_eq!(history.recent(5), [a.to_path_buf(), b.to_path_buf()]);
+    }
+}
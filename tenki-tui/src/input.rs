@@ -0,0 +1,71 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A single-line text input with a cursor, shared by popups and prompts.
+#[derive(Debug, Default, Clone)]
+pub struct Input {
+    pub value: String,
+    /// Cursor position as a byte offset into `value`.
+    pub cursor: usize,
+}
+
+impl Input {
+    pub fn with_value(value: impl Into<String>) -> Self {
+        let value = value.into();
+        Self {
+            cursor: value.len(),
+            value,
+        }
+    }
+
+    /// Apply an editing key. Returns true if the key was handled.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                self.value.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            (KeyCode::Char('w'), KeyModifiers::CONTROL) => {
+                let before = self.value[..self.cursor].trim_end();
+                let start = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+                self.value.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            (KeyCode::Char('a'), KeyModifiers::CONTROL) | (KeyCode::Home, _) => self.cursor = 0,
+            (KeyCode::Char('e'), KeyModifiers::CONTROL) | (KeyCode::End, _) => {
+                self.cursor = self.value.len()
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                self.value.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            (KeyCode::Backspace, _) => {
+                if let Some(c) = self.value[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                    self.value.remove(self.cursor);
+                }
+            }
+            (KeyCode::Delete, _) => {
+                if self.cursor < self.value.len() {
+                    self.value.remove(self.cursor);
+                }
+            }
+            (KeyCode::Left, _) => {
+                if let Some(c) = self.value[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                }
+            }
+            (KeyCode::Right, _) => {
+                if let Some(c) = self.value[self.cursor..].chars().next() {
+                    self.cursor += c.len_utf8();
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Cursor column in characters, for placing the terminal cursor.
+    pub fn cursor_column(&self) -> u16 {
+        self.value[..self.cursor].chars().count() as u16
+    }
+}
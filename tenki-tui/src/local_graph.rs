@@ -0,0 +1,341 @@
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::TAU;
+use std::path::{Path, PathBuf};
+use tenki_core::{NodeIndex, NoteGraph};
+
+/// Steps of the force-directed layout.
+const ITERATIONS: usize = 300;
+/// Most notes drawn at once; the farthest are dropped first.
+const MAX_NODES: usize = 60;
+
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    pub path: PathBuf,
+    pub title: String,
+    /// Links away from the centre note.
+    pub distance: usize,
+    /// Position within -1.0..=1.0 on both axes, `y` pointing up.
+    pub x: f64,
+    pub y: f64,
+}
+
+/// State of the popup drawing the notes around one note.
+#[derive(Debug)]
+pub struct LocalGraph {
+    pub center: NodeIndex,
+    /// 1 or 2 links out from the centre.
+    pub depth: usize,
+    /// The centre note is always first.
+    pub nodes: Vec<GraphNode>,
+    /// Links as positions in `nodes`, each pair of notes once.
+    pub edges: Vec<(usize, usize)>,
+    pub selected: usize,
+    /// Notes left out to keep the drawing readable.
+    pub hidden: usize,
+}
+
+impl LocalGraph {
+    pub fn new(graph: &NoteGraph, center: NodeIndex) -> Self {
+        let mut view = Self {
+            center,
+            depth: 1,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            selected: 0,
+            hidden: 0,
+        };
+        view.update(graph);
+        view
+    }
+
+    /// Switch between showing one and two links out.
+    pub fn toggle_depth(&mut self, graph: &NoteGraph) {
+        self.depth = if self.depth == 1 { 2 } else { 1 };
+        self.update(graph);
+    }
+
+    /// Redraw the graph around the selected note.
+    pub fn recenter(&mut self, graph: &NoteGraph) {
+        if let Some(idx) = self
+            .selected_path()
+            .and_then(|path| graph.find_by_path(path))
+        {
+            self.center = idx;
+            self.update(graph);
+        }
+    }
+
+    fn update(&mut self, graph: &NoteGraph) {
+        let subgraph = graph.neighborhood(self.center, self.depth);
+        // Neighbourhood nodes come breadth-first, so truncating keeps the nearest
+        self.hidden = subgraph.nodes.len().saturating_sub(MAX_NODES);
+        let distances = distances(&subgraph.nodes, &subgraph.edges, self.center);
+        let mut position = HashMap::new();
+        self.nodes = subgraph
+            .nodes
+            .iter()
+            .take(MAX_NODES)
+            .filter_map(|&idx| {
+                let note = graph.get_note(idx)?;
+                position.insert(idx, position.len());
+                Some(GraphNode {
+                    path: note.path.clone(),
+                    title: note.title.clone(),
+                    distance: distances.get(&idx).copied().unwrap_or_default(),
+                    x: 0.0,
+                    y: 0.0,
+                })
+            })
+            .collect();
+        self.edges = Vec::new();
+        let mut seen = HashSet::new();
+        for (a, b) in &subgraph.edges {
+            if let (Some(&a), Some(&b)) = (position.get(a), position.get(b)) {
+                let edge = (a.min(b), a.max(b));
+                if a != b && seen.insert(edge) {
+                    self.edges.push(edge);
+                }
+            }
+        }
+        self.layout();
+        self.selected = 0;
+    }
+
+    /// Fruchterman–Reingold force-directed layout with the centre note pinned
+    /// at the origin. Starts from rings by distance so the result is stable.
+    fn layout(&mut self) {
+        let n = self.nodes.len();
+        let mut rings: HashMap<usize, usize> = HashMap::new();
+        for node in &self.nodes {
+            *rings.entry(node.distance).or_default() += 1;
+        }
+        let mut placed: HashMap<usize, usize> = HashMap::new();
+        for node in &mut self.nodes {
+            let count = rings[&node.distance];
+            let i = placed.entry(node.distance).or_default();
+            let angle = TAU * *i as f64 / count as f64 + node.distance as f64 * 0.5;
+            let radius = node.distance as f64 * 0.5;
+            node.x = radius * angle.cos();
+            node.y = radius * angle.sin();
+            *i += 1;
+        }
+        if n < 2 {
+            return;
+        }
+
+        // Ideal edge length for n nodes spread over the 2 × 2 square
+        let k = 2.0 / (n as f64).sqrt();
+        for iteration in 0..ITERATIONS {
+            let temperature = 0.1 * (1.0 - iteration as f64 / ITERATIONS as f64);
+            let mut shift = vec![(0.0, 0.0); n];
+            for a in 0..n {
+                for b in a + 1..n {
+                    let (dx, dy, d) = self.delta(a, b);
+                    let force = k * k / d;
+                    shift[a].0 += dx / d * force;
+                    shift[a].1 += dy / d * force;
+                    shift[b].0 -= dx / d * force;
+                    shift[b].1 -= dy / d * force;
+                }
+            }
+            for &(a, b) in &self.edges {
+                let (dx, dy, d) = self.delta(a, b);
+                let force = d * d / k;
+                shift[a].0 -= dx / d * force;
+                shift[a].1 -= dy / d * force;
+                shift[b].0 += dx / d * force;
+                shift[b].1 += dy / d * force;
+            }
+            for (node, (sx, sy)) in self.nodes.iter_mut().zip(shift).skip(1) {
+                let length = (sx * sx + sy * sy).sqrt().max(f64::EPSILON);
+                let step = length.min(temperature);
+                node.x = (node.x + sx / length * step).clamp(-1.0, 1.0);
+                node.y = (node.y + sy / length * step).clamp(-1.0, 1.0);
+            }
+        }
+
+        // Stretch the layout to fill the square, keeping the centre in place
+        let extent = self
+            .nodes
+            .iter()
+            .map(|node| node.x.abs().max(node.y.abs()))
+            .fold(0.0, f64::max);
+        if extent > 0.0 {
+            for node in &mut self.nodes {
+                node.x /= extent;
+                node.y /= extent;
+            }
+        }
+    }
+
+    /// Offset from node `b` to node `a` and its length, never zero.
+    fn delta(&self, a: usize, b: usize) -> (f64, f64, f64) {
+        let (a, b) = (&self.nodes[a], &self.nodes[b]);
+        let (dx, dy) = (a.x - b.x, a.y - b.y);
+        let d = (dx * dx + dy * dy).sqrt();
+        if d < 1e-6 {
+            (1e-3, 0.0, 1e-3)
+        } else {
+            (dx, dy, d)
+        }
+    }
+
+    /// Select the nearest node roughly in the direction (`dx`, `dy`), with
+    /// `dy` pointing up.
+    pub fn move_selection(&mut self, dx: f64, dy: f64) {
+        let Some(current) = self.nodes.get(self.selected) else {
+            return;
+        };
+        let best = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != self.selected)
+            .filter_map(|(i, node)| {
+                let (ox, oy) = (node.x - current.x, node.y - current.y);
+                let along = ox * dx + oy * dy;
+                let across = (ox * dy - oy * dx).abs();
+                // Ignore nodes behind or mostly to the side
+                (along > 0.0 && across < along * 2.0).then_some((i, along + across * 2.0))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, _)) = best {
+            self.selected = i;
+        }
+    }
+
+    /// Select the next or previous node in breadth-first order.
+    pub fn cycle_selection(&mut self, forward: bool) {
+        let n = self.nodes.len();
+        if n == 0 {
+            return;
+        }
+        self.selected = if forward {
+            (self.selected + 1) % n
+        } else {
+            (self.selected + n - 1) % n
+        };
+    }
+
+    pub fn selected_path(&self) -> Option<&Path> {
+        self.nodes
+            .get(self.selected)
+            .map(|node| node.path.as_path())
+    }
+
+    /// Number of links between the selected note and others in view.
+    pub fn selected_degree(&self) -> usize {
+        self.edges
+            .iter()
+            .filter(|&&(a, b)| a == self.selected || b == self.selected)
+            .count()
+    }
+}
+
+/// Links between each node and the centre, following links either way.
+fn distances(
+    nodes: &[NodeIndex],
+    edges: &[(NodeIndex, NodeIndex)],
+    center: NodeIndex,
+) -> HashMap<NodeIndex, usize> {
+    let nodes: HashSet<NodeIndex> = nodes.iter().copied().collect();
+    let mut distances = HashMap::from([(center, 0)]);
+    let mut frontier = HashSet::from([center]);
+    while !frontier.is_empty() {
+        let mut next = HashSet::new();
+        for &(a, b) in edges {
+            for (from, to) in [(a, b), (b, a)] {
+                if frontier.contains(&from) && nodes.contains(&to) && !distances.contains_key(&to) {
+                    distances.insert(to, distances[&from] + 1);
+                    next.insert(to);
+                }
+            }
+        }
+        frontier = next;
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, x: f64, y: f64) -> GraphNode {
+        GraphNode {
+            path: PathBuf::from(format!("{name}.md")),
+            title: name.to_string(),
+            distance: 1,
+            x,
+            y,
+        }
+    }
+
+    #[test]
+    fn test_move_selection() {
+        let mut view = LocalGraph {
+            center: NodeIndex::new(0),
+            depth: 1,
+            nodes: vec![
+                node("centre", 0.0, 0.0),
+                node("right", 0.8, 0.1),
+                node("far right", 1.0, 0.0),
+                node("up", -0.1, 0.5),
+                node("down left", -0.6, -0.5),
+            ],
+            edges: Vec::new(),
+            selected: 0,
+            hidden: 0,
+        };
+        let title = |view: &LocalGraph| view.nodes[view.selected].title.clone();
+
+        view.move_selection(1.0, 0.0);
+        assert_eq!(title(&view), "right");
+        view.move_selection(1.0, 0.0);
+        assert_eq!(title(&view), "far right");
+        // Nothing further right: the selection stays
+        view.move_selection(1.0, 0.0);
+        assert_eq!(title(&view), "far right");
+
+        view.selected = 0;
+        view.move_selection(0.0, 1.0);
+        assert_eq!(title(&view), "up");
+        view.move_selection(0.0, -1.0);
+        assert_eq!(title(&view), "centre");
+        // A note below counts even when it is a little off to the side
+        view.move_selection(0.0, -1.0);
+        assert_eq!(title(&view), "down left");
+
+        view.cycle_selection(true);
+        assert_eq!(view.selected, 0);
+        view.cycle_selection(false);
+        assert_eq!(title(&view), "down left");
+    }
+
+    #[test]
+    fn test_hidden_count() {
+        let mut graph = NoteGraph::new();
+        let center = graph.add_note(PathBuf::from("hub.md"), "Hub".into());
+        for i in 0..MAX_NODES + 5 {
+            let idx = graph.add_note(PathBuf::from(format!("{i}.md")), format!("Note {i}"));
+            graph.add_link(center, idx);
+        }
+        let first = graph.find_by_path(Path::new("0.md")).unwrap();
+        let far = graph.add_note(PathBuf::from("far.md"), "Far".into());
+        graph.add_link(first, far);
+
+        let mut view = LocalGraph::new(&graph, center);
+        assert_eq!(view.nodes.len(), MAX_NODES);
+        assert_eq!(view.hidden, 6);
+        assert_eq!(view.nodes[0].title, "Hub");
+        assert!(
+            view.nodes
+                .iter()
+                .all(|node| node.distance == (node.title != "Hub") as usize)
+        );
+
+        // The second ring makes one more note to leave out
+        view.toggle_depth(&graph);
+        assert_eq!(view.nodes.len(), MAX_NODES);
+        assert_eq!(view.hidden, 7);
+    }
+}
@@ -1,23 +1,47 @@
 mod app;
+mod calendar;
+mod chain;
+mod cli;
+mod completion;
 mod editor;
+mod finder;
+mod health;
+mod history;
+mod input;
+mod local_graph;
+mod note_editor;
+mod rename;
+mod search;
+mod templates;
+mod trash;
+mod tree;
 mod ui;
 
-use app::App;
+use app::{App, Pane, Popup, PromptKind};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{env, io, path::PathBuf};
+use editor::EditorCommand;
+use ratatui::{Terminal, backend::CrosstermBackend};
+use std::{env, io, path::Path, time::Duration};
+use tenki_core::Period;
+
+/// How often to check notes open in GUI editors for saves.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Some(result) = cli::run(&args) {
+        return result;
+    }
+
     // Get the notes directory from args or use current directory
-    let notes_dir = env::args()
-        .nth(1)
-        .map(PathBuf::from)
-        .unwrap_or_else(|| env::current_dir().unwrap());
+    let notes_dir = cli::vault_dir(&args)?.unwrap_or_else(|| env::current_dir().unwrap());
 
     let mut app = App::new(notes_dir)?;
 
@@ -54,56 +78,328 @@ async fn run_app(
     loop {
         terminal.draw(|f| ui::draw(f, app))?;
 
+        // Wake up now and then to pick up saves from GUI editors
+        if !event::poll(WATCH_INTERVAL)? {
+            app.check_external_edits();
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
-            match (key.code, key.modifiers) {
-                (KeyCode::Char('q'), _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
-                    return Ok(());
-                }
-                (KeyCode::Char('e'), _) => {
-                    if let Some(path) = app.selected_file() {
-                        // Suspend TUI and open editor
-                        disable_raw_mode()?;
-                        execute!(
-                            terminal.backend_mut(),
-                            LeaveAlternateScreen,
-                            DisableMouseCapture
-                        )?;
-
-                        editor::open_in_editor(&path)?;
-
-                        // Resume TUI
-                        enable_raw_mode()?;
-                        execute!(
-                            terminal.backend_mut(),
-                            EnterAlternateScreen,
-                            EnableMouseCapture
-                        )?;
-                        terminal.clear()?;
-
-                        // Refresh the graph after editing
-                        app.refresh()?;
-                    }
-                }
-                (KeyCode::Tab, _) => {
-                    app.next_pane();
-                }
-                (KeyCode::BackTab, _) => {
-                    app.prev_pane();
-                }
-                (KeyCode::Up | KeyCode::Char('k'), _) => {
-                    app.move_up();
-                }
-                (KeyCode::Down | KeyCode::Char('j'), _) => {
-                    app.move_down();
+            app.message = None;
+            if app.completion_key(key) {
+                // Taken by the link suggestions
+            } else if app.popup.is_some() {
+                handle_popup_key(app, key);
+            } else if app.editor.is_some() {
+                app.editor_key(key);
+            } else if handle_key(app, key)? {
+                return Ok(());
+            }
+            app.update_completion();
+            if let Some(path) = app.pending_edit.take() {
+                edit_note(terminal, app, &path)?;
+            }
+        }
+    }
+}
+
+/// Open the note in $EDITOR at the selected line. A terminal editor takes
+/// over the screen until it exits and the note is re-indexed; a GUI editor
+/// runs beside the TUI, which re-reads the note whenever it is saved.
+fn edit_note(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    path: &Path,
+) -> io::Result<()> {
+    let editor = EditorCommand::from_env();
+    let line = app.edit_line();
+    if editor.is_gui() {
+        match editor.spawn(path, line) {
+            Ok(child) => {
+                app.message = Some(format!("Opened in {}", editor.program));
+                app.watch_edit(path.to_path_buf(), child);
+            }
+            Err(e) => app.message = Some(format!("Couldn't start {}: {e}", editor.program)),
+        }
+        return Ok(());
+    }
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+
+    let result = editor.run(path, line);
+
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+    result?;
+
+    app.reload_file(path)
+}
+
+/// Handle a key in the main view. Returns true when the app should quit.
+fn handle_key(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    match (key.code, key.modifiers) {
+        (KeyCode::Char('q'), _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+            return Ok(true);
+        }
+        (KeyCode::Char('e'), _) => {
+            app.pending_edit = app.selected_file();
+        }
+        (KeyCode::Char('i'), _) => {
+            app.open_builtin_editor();
+        }
+        (KeyCode::Char('a'), _) => {
+            app.open_new_note_prompt();
+        }
+        (KeyCode::Char('d'), _) => {
+            app.open_periodic(Period::Day);
+        }
+        (KeyCode::Char('w'), _) => {
+            app.open_periodic(Period::Week);
+        }
+        (KeyCode::Char('m'), _) => {
+            app.open_periodic(Period::Month);
+        }
+        (KeyCode::Char('['), _) => {
+            app.step_periodic(false);
+        }
+        (KeyCode::Char(']'), _) => {
+            app.step_periodic(true);
+        }
+        (KeyCode::Char('R'), _) => {
+            app.open_rename_prompt();
+        }
+        (KeyCode::Char('D'), _) => {
+            app.open_delete_confirm();
+        }
+        (KeyCode::Char('T'), _) => {
+            app.open_trash();
+        }
+        (KeyCode::Char('u'), _) => {
+            app.undo();
+        }
+        (KeyCode::Char('o'), KeyModifiers::CONTROL)
+        | (KeyCode::Backspace, _)
+        | (KeyCode::Left, KeyModifiers::ALT) => {
+            app.go_back();
+        }
+        // Ctrl-i arrives as Tab in most terminals, so forward is Alt-Right only
+        (KeyCode::Right, KeyModifiers::ALT) => {
+            app.go_forward();
+        }
+        (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+            app.open_finder();
+        }
+        (KeyCode::Char('/'), _) if app.active_pane == Pane::Files => {
+            app.open_finder();
+        }
+        (KeyCode::Char('f'), KeyModifiers::CONTROL) => {
+            app.open_search();
+        }
+        (KeyCode::Char('n'), KeyModifiers::NONE) => {
+            app.search_step(true);
+        }
+        (KeyCode::Char('N'), _) => {
+            app.search_step(false);
+        }
+        (KeyCode::Char(':'), _) => {
+            app.open_query_prompt();
+        }
+        (KeyCode::Char('C'), _) => {
+            app.toggle_calendar();
+        }
+        (KeyCode::Char('l') | KeyCode::Right, _) if app.active_pane == Pane::Calendar => {
+            app.calendar_move(1);
+        }
+        (KeyCode::Char('h') | KeyCode::Left, _) if app.active_pane == Pane::Calendar => {
+            app.calendar_move(-1);
+        }
+        (KeyCode::Char('>'), _) if app.active_pane == Pane::Calendar => {
+            app.calendar_month(true);
+        }
+        (KeyCode::Char('<'), _) if app.active_pane == Pane::Calendar => {
+            app.calendar_month(false);
+        }
+        (KeyCode::Char(' '), _) if app.active_pane == Pane::Calendar => {
+            app.open_day_view();
+        }
+        (KeyCode::Char('l') | KeyCode::Right, _) if app.active_pane == Pane::Files => {
+            app.expand_selected();
+        }
+        (KeyCode::Char('h') | KeyCode::Left, _) if app.active_pane == Pane::Files => {
+            app.collapse_selected();
+        }
+        (KeyCode::Char('t'), _) => {
+            app.toggle_tree_view();
+        }
+        (KeyCode::Char('P'), _) => {
+            app.open_chain_finder();
+        }
+        (KeyCode::Char('H'), _) => {
+            app.open_health();
+        }
+        (KeyCode::Char('G'), _) => {
+            app.open_local_graph();
+        }
+        (KeyCode::Char('f'), KeyModifiers::NONE) => {
+            app.open_filter_prompt();
+        }
+        (KeyCode::Char('s'), _) => {
+            app.cycle_sort();
+        }
+        (KeyCode::Char('o'), KeyModifiers::NONE) => {
+            app.reverse_sort();
+        }
+        (KeyCode::Char('S'), _) => {
+            app.open_save_search_prompt();
+        }
+        (KeyCode::Char('x'), _) => {
+            app.remove_selected_search();
+        }
+        (KeyCode::Esc, _) => {
+            app.clear_query();
+        }
+        (KeyCode::Tab, _) => {
+            app.next_pane();
+        }
+        (KeyCode::BackTab, _) => {
+            app.prev_pane();
+        }
+        (KeyCode::Up | KeyCode::Char('k'), _) => {
+            app.move_up();
+        }
+        (KeyCode::Down | KeyCode::Char('j'), _) => {
+            app.move_down();
+        }
+        (KeyCode::Enter, _) => {
+            app.select();
+        }
+        (KeyCode::Char(' '), _) => {
+            app.toggle_backlink();
+        }
+        (KeyCode::Char('L'), _) => {
+            app.link_selected_mention()?;
+        }
+        (KeyCode::Char('r'), _) => {
+            app.refresh()?;
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn handle_popup_key(app: &mut App, key: KeyEvent) {
+    let Some(popup) = app.popup.as_mut() else {
+        return;
+    };
+    match popup {
+        Popup::Finder(finder) => match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => app.popup = None,
+            (KeyCode::Enter, _) => app.finder_select(),
+            (KeyCode::Up, _) | (KeyCode::Char('p' | 'k'), KeyModifiers::CONTROL) => {
+                finder.move_up()
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('n' | 'j'), KeyModifiers::CONTROL) => {
+                finder.move_down()
+            }
+            _ => {
+                if finder.input.handle_key(key) {
+                    finder.update(&app.graph, app.scanner.root());
                 }
-                (KeyCode::Enter, _) => {
-                    app.select();
+            }
+        },
+        Popup::Prompt(prompt) => match key.code {
+            KeyCode::Esc => app.cancel_prompt(),
+            KeyCode::Enter => app.submit_prompt(),
+            _ => {
+                if prompt.input.handle_key(key) && prompt.kind == PromptKind::Filter {
+                    let filter = prompt.input.value.clone();
+                    app.set_filter(&filter);
                 }
-                (KeyCode::Char('r'), _) => {
-                    app.refresh()?;
+            }
+        },
+        Popup::Chain(chain) => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.popup = None,
+            KeyCode::Enter => app.chain_select(),
+            KeyCode::Char('d') => chain.toggle_directed(&app.graph),
+            KeyCode::Up | KeyCode::Char('k') => chain.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => chain.move_down(),
+            _ => {}
+        },
+        Popup::Graph(view) => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.popup = None,
+            KeyCode::Enter => app.local_graph_select(),
+            KeyCode::Char('c') => view.recenter(&app.graph),
+            KeyCode::Char('d') => view.toggle_depth(&app.graph),
+            KeyCode::Left | KeyCode::Char('h') => view.move_selection(-1.0, 0.0),
+            KeyCode::Right | KeyCode::Char('l') => view.move_selection(1.0, 0.0),
+            KeyCode::Up | KeyCode::Char('k') => view.move_selection(0.0, 1.0),
+            KeyCode::Down | KeyCode::Char('j') => view.move_selection(0.0, -1.0),
+            KeyCode::Tab => view.cycle_selection(true),
+            KeyCode::BackTab => view.cycle_selection(false),
+            _ => {}
+        },
+        Popup::Rename(preview) => match key.code {
+            KeyCode::Esc | KeyCode::Char('q' | 'n') => app.popup = None,
+            KeyCode::Enter | KeyCode::Char('y') => app.apply_rename(),
+            KeyCode::Up | KeyCode::Char('k') => preview.scroll_up(),
+            KeyCode::Down | KeyCode::Char('j') => preview.scroll_down(),
+            _ => {}
+        },
+        Popup::Delete(_) => match key.code {
+            KeyCode::Enter | KeyCode::Char('y') => app.delete_confirmed(),
+            KeyCode::Esc | KeyCode::Char('q' | 'n') => app.popup = None,
+            _ => {}
+        },
+        Popup::Templates(picker) => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.popup = None,
+            KeyCode::Enter => app.template_selected(),
+            KeyCode::Up | KeyCode::Char('k') => picker.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => picker.move_down(),
+            _ => {}
+        },
+        Popup::Day(view) => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.popup = None,
+            KeyCode::Enter => app.day_view_select(),
+            KeyCode::Up | KeyCode::Char('k') => view.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => view.move_down(),
+            _ => {}
+        },
+        Popup::Trash(view) => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.popup = None,
+            KeyCode::Enter => app.restore_selected(),
+            KeyCode::Up | KeyCode::Char('k') => view.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => view.move_down(),
+            _ => {}
+        },
+        Popup::Health(view) => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.popup = None,
+            KeyCode::Enter => app.health_select(),
+            KeyCode::Up | KeyCode::Char('k') => view.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => view.move_down(),
+            _ => {}
+        },
+        Popup::Search(panel) => match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => app.close_search(),
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) => panel.toggle_order(&app.graph),
+            (KeyCode::Enter, _) => app.search_select(),
+            (KeyCode::Up, _) | (KeyCode::Char('p' | 'k'), KeyModifiers::CONTROL) => panel.move_up(),
+            (KeyCode::Down, _) | (KeyCode::Char('n' | 'j'), KeyModifiers::CONTROL) => {
+                panel.move_down()
+            }
+            _ => {
+                if panel.input.handle_key(key) {
+                    panel.update(&app.graph);
                 }
-                _ => {}
             }
-        }
+        },
     }
 }
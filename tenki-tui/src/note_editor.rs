@@ -0,0 +1,683 @@
+use crate::input::Input;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cell::Cell;
+use std::path::PathBuf;
+
+/// Vim-style editing mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Insert,
+    /// Typing an ex command after `:`.
+    Command,
+}
+
+/// What the app should do after a key in the editor.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EditorAction {
+    None,
+    /// Write the note; `force` overwrites changes made outside tenki.
+    Save {
+        force: bool,
+    },
+    /// Close the editor; `force` drops unsaved changes.
+    Close {
+        force: bool,
+    },
+    SaveAndClose,
+    /// Throw away the buffer and read the note again.
+    Reload,
+    /// An ex command the editor doesn't know.
+    Unknown(String),
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+/// A note being edited in the built-in editor, in place of the preview.
+#[derive(Debug)]
+pub struct NoteEditor {
+    pub path: PathBuf,
+    pub lines: Vec<String>,
+    pub row: usize,
+    /// Cursor column in characters.
+    pub col: usize,
+    pub mode: Mode,
+    pub command: Input,
+    /// File content when opened or last saved, to spot changes made
+    /// outside the editor.
+    pub original: String,
+    /// First row and column shown, moved by drawing to keep the cursor visible.
+    scroll: Cell<(usize, usize)>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// First key of a two-key command such as `dd` or `gg`.
+    pending: Option<char>,
+    /// Lines taken with `yy` or `dd`, for `p`.
+    register: Vec<String>,
+}
+
+/// Byte offset of character `col` in `line`, or its length past the end.
+fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+}
+
+fn char_count(line: &str) -> usize {
+    line.chars().count()
+}
+
+/// Character class for word motions: blanks, word characters, punctuation.
+fn class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+impl NoteEditor {
+    pub fn new(path: PathBuf, content: String) -> Self {
+        Self {
+            path,
+            lines: content.split('\n').map(str::to_string).collect(),
+            row: 0,
+            col: 0,
+            mode: Mode::Normal,
+            command: Input::default(),
+            original: content,
+            scroll: Cell::new((0, 0)),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            pending: None,
+            register: Vec::new(),
+        }
+    }
+
+    pub fn content(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.content() != self.original
+    }
+
+    /// Replace the buffer with the note as it is on disk.
+    pub fn reload(&mut self, content: String) {
+        self.checkpoint();
+        self.lines = content.split('\n').map(str::to_string).collect();
+        self.original = content;
+        self.row = self.row.min(self.lines.len() - 1);
+        self.clamp_col();
+    }
+
+    /// Move the first shown row and column so the cursor fits in a view of
+    /// the given size, and return them.
+    pub fn scroll_into_view(&self, height: usize, width: usize) -> (usize, usize) {
+        let (mut top, mut left) = self.scroll.get();
+        if self.row < top {
+            top = self.row;
+        } else if height > 0 && self.row >= top + height {
+            top = self.row + 1 - height;
+        }
+        if self.col < left {
+            left = self.col;
+        } else if width > 0 && self.col >= left + width {
+            left = self.col + 1 - width;
+        }
+        self.scroll.set((top, left));
+        (top, left)
+    }
+
+    fn line(&self) -> &str {
+        &self.lines[self.row]
+    }
+
+    /// The cursor's line and the cursor's byte offset in it.
+    pub fn cursor_line(&self) -> (&str, usize) {
+        (self.line(), byte_index(self.line(), self.col))
+    }
+
+    /// Change the cursor's line with `edit`, which gets the cursor's byte
+    /// offset and returns where the cursor goes.
+    pub fn edit_line(&mut self, edit: impl FnOnce(&mut String, usize) -> usize) {
+        let cursor = byte_index(self.line(), self.col);
+        let cursor = edit(&mut self.lines[self.row], cursor);
+        self.col = self.lines[self.row]
+            .get(..cursor)
+            .map_or(self.col, char_count);
+    }
+
+    /// Keep the cursor on a character; insert mode may sit past the last one.
+    fn clamp_col(&mut self) {
+        let len = char_count(self.line());
+        let max = if self.mode == Mode::Insert {
+            len
+        } else {
+            len.saturating_sub(1)
+        };
+        self.col = self.col.min(max);
+    }
+
+    /// Remember the buffer before a change, for `u`.
+    fn checkpoint(&mut self) {
+        self.undo.push(Snapshot {
+            lines: self.lines.clone(),
+            row: self.row,
+            col: self.col,
+        });
+        self.redo.clear();
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.lines = snapshot.lines;
+        self.row = snapshot.row.min(self.lines.len() - 1);
+        self.col = snapshot.col;
+        self.clamp_col();
+    }
+
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo.pop() {
+            self.redo.push(Snapshot {
+                lines: self.lines.clone(),
+                row: self.row,
+                col: self.col,
+            });
+            self.restore(snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo.pop() {
+            self.undo.push(Snapshot {
+                lines: self.lines.clone(),
+                row: self.row,
+                col: self.col,
+            });
+            self.restore(snapshot);
+        }
+    }
+
+    fn enter_insert(&mut self) {
+        self.checkpoint();
+        self.mode = Mode::Insert;
+        self.clamp_col();
+    }
+
+    fn leave_insert(&mut self) {
+        self.mode = Mode::Normal;
+        // An insert that changed nothing isn't worth an undo step
+        if self
+            .undo
+            .last()
+            .is_some_and(|snapshot| snapshot.lines == self.lines)
+        {
+            self.undo.pop();
+        }
+        self.col = self.col.saturating_sub(1);
+        self.clamp_col();
+    }
+
+    fn move_row(&mut self, delta: isize) {
+        self.row = self
+            .row
+            .saturating_add_signed(delta)
+            .min(self.lines.len() - 1);
+        self.clamp_col();
+    }
+
+    fn first_non_blank(&mut self) {
+        self.col = self
+            .line()
+            .chars()
+            .position(|c| !c.is_whitespace())
+            .unwrap_or(0);
+    }
+
+    /// `w`: start of the next word, possibly on a later line.
+    fn word_forward(&mut self) {
+        let chars: Vec<char> = self.line().chars().collect();
+        let mut col = self.col;
+        if let Some(&c) = chars.get(col) {
+            let start = class(c);
+            while col < chars.len() && start != 0 && class(chars[col]) == start {
+                col += 1;
+            }
+        }
+        while col < chars.len() && class(chars[col]) == 0 {
+            col += 1;
+        }
+        if col < chars.len() || self.row + 1 == self.lines.len() {
+            self.col = col;
+        } else {
+            self.row += 1;
+            self.first_non_blank();
+        }
+        self.clamp_col();
+    }
+
+    /// `b`: start of the previous word, possibly on an earlier line.
+    fn word_back(&mut self) {
+        if self.col == 0 {
+            if self.row > 0 {
+                self.row -= 1;
+                self.col = char_count(self.line());
+                self.clamp_col();
+            }
+            return;
+        }
+        let chars: Vec<char> = self.line().chars().collect();
+        let mut col = self.col.min(chars.len());
+        while col > 0 && class(chars[col - 1]) == 0 {
+            col -= 1;
+        }
+        if col > 0 {
+            let word = class(chars[col - 1]);
+            while col > 0 && class(chars[col - 1]) == word {
+                col -= 1;
+            }
+        }
+        self.col = col;
+    }
+
+    fn insert_char(&mut self, c: char) {
+        let at = byte_index(self.line(), self.col);
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+    }
+
+    fn insert_newline(&mut self) {
+        let at = byte_index(self.line(), self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = byte_index(self.line(), self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = char_count(self.line());
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete_char(&mut self) {
+        if self.col < char_count(self.line()) {
+            let at = byte_index(self.line(), self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    fn open_line(&mut self, below: bool) {
+        self.enter_insert();
+        let at = if below { self.row + 1 } else { self.row };
+        self.lines.insert(at, String::new());
+        self.row = at;
+        self.col = 0;
+    }
+
+    fn delete_line(&mut self) {
+        self.checkpoint();
+        self.register = vec![self.lines.remove(self.row)];
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.row = self.row.min(self.lines.len() - 1);
+        self.first_non_blank();
+    }
+
+    fn paste(&mut self, below: bool) {
+        if self.register.is_empty() {
+            return;
+        }
+        self.checkpoint();
+        let at = if below { self.row + 1 } else { self.row };
+        for (i, line) in self.register.iter().enumerate() {
+            self.lines.insert(at + i, line.clone());
+        }
+        self.row = at;
+        self.first_non_blank();
+    }
+
+    /// Apply a key and say what, if anything, the app has to do.
+    pub fn handle_key(&mut self, key: KeyEvent) -> EditorAction {
+        if key.code == KeyCode::Char('s') && key.modifiers == KeyModifiers::CONTROL {
+            if self.mode == Mode::Insert {
+                self.leave_insert();
+            }
+            return EditorAction::Save { force: false };
+        }
+        match self.mode {
+            Mode::Normal => self.normal_key(key),
+            Mode::Insert => {
+                self.insert_key(key);
+                EditorAction::None
+            }
+            Mode::Command => self.command_key(key),
+        }
+    }
+
+    fn normal_key(&mut self, key: KeyEvent) -> EditorAction {
+        let pending = self.pending.take();
+        match (pending, key.code, key.modifiers) {
+            (Some('d'), KeyCode::Char('d'), _) => self.delete_line(),
+            (Some('y'), KeyCode::Char('y'), _) => self.register = vec![self.line().to_string()],
+            (Some('g'), KeyCode::Char('g'), _) => {
+                self.row = 0;
+                self.first_non_blank();
+            }
+            (Some(_), _, _) => {}
+            (None, KeyCode::Char(c @ ('d' | 'y' | 'g')), KeyModifiers::NONE) => {
+                self.pending = Some(c)
+            }
+            (None, KeyCode::Char('r'), KeyModifiers::CONTROL) => self.redo(),
+            (None, KeyCode::Char('h') | KeyCode::Left, _) | (None, KeyCode::Backspace, _) => {
+                self.col = self.col.saturating_sub(1)
+            }
+            (None, KeyCode::Char('l') | KeyCode::Right, _) => {
+                self.col += 1;
+                self.clamp_col();
+            }
+            (None, KeyCode::Char('j') | KeyCode::Down, _) => self.move_row(1),
+            (None, KeyCode::Char('k') | KeyCode::Up, _) => self.move_row(-1),
+            (None, KeyCode::PageDown, _) => self.move_row(20),
+            (None, KeyCode::PageUp, _) => self.move_row(-20),
+            (None, KeyCode::Char('0') | KeyCode::Home, _) => self.col = 0,
+            (None, KeyCode::Char('^'), _) => self.first_non_blank(),
+            (None, KeyCode::Char('$') | KeyCode::End, _) => {
+                self.col = usize::MAX;
+                self.clamp_col();
+            }
+            (None, KeyCode::Char('w'), _) => self.word_forward(),
+            (None, KeyCode::Char('b'), _) => self.word_back(),
+            (None, KeyCode::Char('G'), _) => {
+                self.row = self.lines.len() - 1;
+                self.first_non_blank();
+            }
+            (None, KeyCode::Char('i'), _) => self.enter_insert(),
+            (None, KeyCode::Char('a'), _) => {
+                self.enter_insert();
+                self.col = (self.col + 1).min(char_count(self.line()));
+            }
+            (None, KeyCode::Char('I'), _) => {
+                self.enter_insert();
+                self.first_non_blank();
+            }
+            (None, KeyCode::Char('A'), _) => {
+                self.enter_insert();
+                self.col = char_count(self.line());
+            }
+            (None, KeyCode::Char('o'), _) => self.open_line(true),
+            (None, KeyCode::Char('O'), _) => self.open_line(false),
+            (None, KeyCode::Char('x') | KeyCode::Delete, _) if !self.line().is_empty() => {
+                self.checkpoint();
+                self.delete_char();
+                self.clamp_col();
+            }
+            (None, KeyCode::Char('p'), _) => self.paste(true),
+            (None, KeyCode::Char('P'), _) => self.paste(false),
+            (None, KeyCode::Char('u'), _) => self.undo(),
+            (None, KeyCode::Char(':'), _) => {
+                self.mode = Mode::Command;
+                self.command = Input::default();
+            }
+            _ => {}
+        }
+        EditorAction::None
+    }
+
+    fn insert_key(&mut self, key: KeyEvent) {
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => self.leave_insert(),
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => self.insert_char(c),
+            (KeyCode::Tab, _) => {
+                for _ in 0..4 {
+                    self.insert_char(' ');
+                }
+            }
+            (KeyCode::Enter, _) => self.insert_newline(),
+            (KeyCode::Backspace, _) => self.backspace(),
+            (KeyCode::Delete, _) => self.delete_char(),
+            (KeyCode::Left, _) => self.col = self.col.saturating_sub(1),
+            (KeyCode::Right, _) => {
+                self.col += 1;
+                self.clamp_col();
+            }
+            (KeyCode::Up, _) => self.move_row(-1),
+            (KeyCode::Down, _) => self.move_row(1),
+            (KeyCode::Home, _) => self.col = 0,
+            (KeyCode::End, _) => self.col = char_count(self.line()),
+            _ => {}
+        }
+    }
+
+    fn command_key(&mut self, key: KeyEvent) -> EditorAction {
+        match key.code {
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                return match self.command.value.trim() {
+                    "w" => EditorAction::Save { force: false },
+                    "w!" => EditorAction::Save { force: true },
+                    "q" => EditorAction::Close { force: false },
+                    "q!" => EditorAction::Close { force: true },
+                    "wq" | "x" => EditorAction::SaveAndClose,
+                    "e!" => EditorAction::Reload,
+                    "" => EditorAction::None,
+                    other => EditorAction::Unknown(other.to_string()),
+                };
+            }
+            KeyCode::Backspace if self.command.value.is_empty() => self.mode = Mode::Normal,
+            _ => {
+                self.command.handle_key(key);
+            }
+        }
+        EditorAction::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(content: &str) -> NoteEditor {
+        NoteEditor::new(PathBuf::from("note.md"), content.to_string())
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    /// Type each character as a key, returning the last action.
+    fn keys(editor: &mut NoteEditor, text: &str) -> EditorAction {
+        let mut action = EditorAction::None;
+        for c in text.chars() {
+            action = editor.handle_key(key(KeyCode::Char(c)));
+        }
+        action
+    }
+
+    fn esc(editor: &mut NoteEditor) {
+        editor.handle_key(key(KeyCode::Esc));
+    }
+
+    #[test]
+    fn test_insert_and_normal_modes() {
+        let mut editor = editor("world");
+        keys(&mut editor, "ihello ");
+        assert_eq!(editor.mode, Mode::Insert);
+        assert_eq!(editor.col, 6);
+        esc(&mut editor);
+        // Leaving insert mode steps back onto the last character typed
+        assert_eq!(editor.mode, Mode::Normal);
+        assert_eq!(editor.col, 5);
+        assert_eq!(editor.content(), "hello world");
+        assert!(editor.is_modified());
+
+        keys(&mut editor, "A!");
+        esc(&mut editor);
+        keys(&mut editor, "I> ");
+        esc(&mut editor);
+        assert_eq!(editor.content(), "> hello world!");
+
+        keys(&mut editor, "obelow");
+        esc(&mut editor);
+        keys(&mut editor, "Oabove");
+        esc(&mut editor);
+        assert_eq!(editor.content(), "> hello world!\nabove\nbelow");
+        assert_eq!(editor.row, 1);
+
+        keys(&mut editor, "a");
+        assert_eq!(
+            editor.handle_key(ctrl('s')),
+            EditorAction::Save { force: false }
+        );
+        assert_eq!(editor.mode, Mode::Normal);
+
+        keys(&mut editor, ":wq");
+        assert_eq!(editor.mode, Mode::Command);
+        assert_eq!(
+            editor.handle_key(key(KeyCode::Enter)),
+            EditorAction::SaveAndClose
+        );
+        keys(&mut editor, ":nope");
+        assert_eq!(
+            editor.handle_key(key(KeyCode::Enter)),
+            EditorAction::Unknown("nope".into())
+        );
+        keys(&mut editor, ":");
+        editor.handle_key(key(KeyCode::Backspace));
+        assert_eq!(editor.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn test_motions_at_edges() {
+        let mut editor = editor("héllo wörld\n  ünï, code\n");
+        // Nothing above the first line or left of the first column
+        keys(&mut editor, "kh");
+        assert_eq!((editor.row, editor.col), (0, 0));
+
+        keys(&mut editor, "$");
+        assert_eq!(editor.col, 10);
+        keys(&mut editor, "l");
+        assert_eq!(editor.col, 10);
+        assert_eq!(editor.cursor_line(), ("héllo wörld", 12));
+
+        // `w` from the last word goes to the next line's first word
+        keys(&mut editor, "0ww");
+        assert_eq!((editor.row, editor.col), (1, 2));
+        keys(&mut editor, "w");
+        assert_eq!(editor.col, 5);
+        keys(&mut editor, "w");
+        assert_eq!(editor.col, 7);
+        keys(&mut editor, "bb");
+        assert_eq!(editor.col, 2);
+        keys(&mut editor, "0b");
+        assert_eq!((editor.row, editor.col), (0, 10));
+
+        // The trailing newline leaves an empty last line
+        keys(&mut editor, "G");
+        assert_eq!((editor.row, editor.col), (2, 0));
+        keys(&mut editor, "jl");
+        assert_eq!((editor.row, editor.col), (2, 0));
+        keys(&mut editor, "gg^");
+        assert_eq!((editor.row, editor.col), (0, 0));
+
+        // Edits land on character boundaries
+        keys(&mut editor, "lx");
+        assert_eq!(editor.lines[0], "hllo wörld");
+        keys(&mut editor, "$x");
+        assert_eq!(editor.lines[0], "hllo wörl");
+        assert_eq!(editor.col, 8);
+        keys(&mut editor, "jA…");
+        editor.handle_key(key(KeyCode::Backspace));
+        editor.handle_key(key(KeyCode::Backspace));
+        assert_eq!(editor.lines[1], "  ünï, cod");
+        editor.handle_key(key(KeyCode::Home));
+        editor.handle_key(key(KeyCode::Backspace));
+        assert_eq!(editor.lines[0], "hllo wörl  ünï, cod");
+        assert_eq!((editor.row, editor.col), (0, 9));
+        editor.handle_key(key(KeyCode::Enter));
+        assert_eq!(editor.content(), "hllo wörl\n  ünï, cod\n");
+    }
+
+    #[test]
+    fn test_delete_yank_paste() {
+        let mut editor = editor("one\n  two\nthree");
+        keys(&mut editor, "jdd");
+        assert_eq!(editor.content(), "one\nthree");
+        assert_eq!(editor.row, 1);
+        keys(&mut editor, "p");
+        assert_eq!(editor.content(), "one\nthree\n  two");
+        // Pasting moves to the first non-blank of the pasted line
+        assert_eq!((editor.row, editor.col), (2, 2));
+
+        keys(&mut editor, "ggyyGP");
+        assert_eq!(editor.content(), "one\nthree\none\n  two");
+        assert_eq!(editor.row, 2);
+
+        // A pending `d` is dropped by any other key
+        keys(&mut editor, "djdd");
+        assert_eq!(editor.content(), "one\nthree\n  two");
+
+        keys(&mut editor, "dddddd");
+        assert_eq!(editor.content(), "");
+        keys(&mut editor, "x");
+        assert_eq!(editor.content(), "");
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut editor = editor("abc");
+        keys(&mut editor, "x");
+        keys(&mut editor, "Adef");
+        esc(&mut editor);
+        assert_eq!(editor.content(), "bcdef");
+
+        // An insert that changed nothing leaves no undo step
+        keys(&mut editor, "i");
+        esc(&mut editor);
+
+        keys(&mut editor, "u");
+        assert_eq!(editor.content(), "bc");
+        keys(&mut editor, "u");
+        assert_eq!(editor.content(), "abc");
+        keys(&mut editor, "u");
+        assert_eq!(editor.content(), "abc");
+
+        editor.handle_key(ctrl('r'));
+        assert_eq!(editor.content(), "bc");
+        editor.handle_key(ctrl('r'));
+        assert_eq!(editor.content(), "bcdef");
+
+        // A new change drops what could be redone
+        keys(&mut editor, "uu0x");
+        editor.handle_key(ctrl('r'));
+        assert_eq!(editor.content(), "bc");
+        assert!(editor.is_modified());
+
+        editor.reload("fresh".into());
+        assert_eq!(editor.content(), "fresh");
+        assert!(!editor.is_modified());
+        keys(&mut editor, "u");
+        assert_eq!(editor.content(), "bc");
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::Path;
+use tenki_core::RenamePlan;
+
+/// One line of the rename preview.
+#[derive(Debug)]
+pub enum PreviewRow {
+    /// A file that changes, or the note's move.
+    Heading(String),
+    Removed {
+        line: usize,
+        text: String,
+    },
+    Added {
+        line: usize,
+        text: String,
+    },
+}
+
+/// State of the popup showing what a rename will change before applying it.
+#[derive(Debug)]
+pub struct RenamePreview {
+    pub plan: RenamePlan,
+    pub rows: Vec<PreviewRow>,
+    pub scroll: usize,
+}
+
+impl RenamePreview {
+    pub fn new(plan: RenamePlan, root: &Path) -> Self {
+        let relative = |path: &Path| {
+            path.strip_prefix(root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+        let mut rows = Vec::new();
+        if plan.to != plan.from {
+            rows.push(PreviewRow::Heading(format!(
+                "move {} → {}",
+                relative(&plan.from),
+                relative(&plan.to)
+            )));
+        }
+        for file in &plan.files {
+            rows.push(PreviewRow::Heading(relative(&file.path)));
+            for change in file.changed_lines() {
+                for (i, text) in change.before.lines().enumerate() {
+                    rows.push(PreviewRow::Removed {
+                        line: change.line + i,
+                        text: text.to_string(),
+                    });
+                }
+                for (i, text) in change.after.lines().enumerate() {
+                    rows.push(PreviewRow::Added {
+                        line: change.line + i,
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+        Self {
+            plan,
+            rows,
+            scroll: 0,
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.rows.len() {
+            self.scroll += 1;
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::input::Input;
+use std::path::Path;
+use tenki_core::{NoteGraph, SearchHit, Snippet};
+
+/// State of the full-text search panel. Kept after closing so `n`/`N` can
+/// step through the last results.
+#[derive(Debug, Default)]
+pub struct SearchPanel {
+    pub input: Input,
+    pub hits: Vec<SearchHit>,
+    /// Flattened (hit, line) pairs, one per selectable row.
+    pub entries: Vec<(usize, usize)>,
+    pub selected: usize,
+    pub error: Option<String>,
+    /// Order hits by note importance instead of relevance.
+    pub by_importance: bool,
+}
+
+impl SearchPanel {
+    /// Re-run the query against the graph's search index.
+    pub fn update(&mut self, graph: &NoteGraph) {
+        self.selected = 0;
+        match graph.search_index().search(&self.input.value) {
+            Ok(mut hits) => {
+                if self.by_importance {
+                    let rank = |hit: &SearchHit| {
+                        graph
+                            .find_by_path(&hit.path)
+                            .map_or(0.0, |idx| graph.ranking().pagerank(idx))
+                    };
+                    hits.sort_by(|a, b| rank(b).total_cmp(&rank(a)));
+                }
+                self.entries = hits
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(h, hit)| (0..hit.lines.len()).map(move |l| (h, l)))
+                    .collect();
+                self.hits = hits;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// Switch between relevance and importance order.
+    pub fn toggle_order(&mut self, graph: &NoteGraph) {
+        self.by_importance = !self.by_importance;
+        self.update(graph);
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected < self.entries.len().saturating_sub(1) {
+            self.selected += 1;
+        }
+    }
+
+    /// Step to the next match, wrapping around.
+    pub fn next(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + 1) % self.entries.len();
+        }
+    }
+
+    /// Step to the previous match, wrapping around.
+    pub fn prev(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + self.entries.len() - 1) % self.entries.len();
+        }
+    }
+
+    /// The note and line of the selected match.
+    pub fn selected(&self) -> Option<(&Path, &Snippet)> {
+        let &(h, l) = self.entries.get(self.selected)?;
+        let hit = &self.hits[h];
+        Some((&hit.path, &hit.lines[l]))
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+use tenki_core::{Template, TemplateValues};
+
+/// State of the popup choosing what a new note starts from.
+#[derive(Debug)]
+pub struct TemplatePicker {
+    pub folder: PathBuf,
+    pub title: String,
+    pub templates: Vec<Template>,
+    /// 0 is a blank note, then one row per template.
+    pub selected: usize,
+}
+
+impl TemplatePicker {
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected < self.templates.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// The chosen template, `None` for a blank note.
+    pub fn selected(&self) -> Option<&Template> {
+        self.selected
+            .checked_sub(1)
+            .and_then(|i| self.templates.get(i))
+    }
+}
+
+/// A note being created from a template while its prompts are answered.
+#[derive(Debug)]
+pub struct PendingNote {
+    pub folder: PathBuf,
+    pub template: Template,
+    pub values: TemplateValues,
+    pub questions: Vec<String>,
+}
+
+impl PendingNote {
+    /// The next prompt to ask, if any are left.
+    pub fn question(&self) -> Option<&str> {
+        self.questions
+            .get(self.values.answers.len())
+            .map(String::as_str)
+    }
+
+    pub fn answer(&mut self, answer: String) {
+        if let Some(question) = self.question() {
+            self.values.answers.insert(question.to_string(), answer);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+use tenki_core::{NoteGraph, TrashEntry};
+
+/// State of the popup asking before a note is moved to the trash.
+#[derive(Debug)]
+pub struct DeleteConfirm {
+    pub path: PathBuf,
+    pub title: String,
+    /// Titles of the notes whose links to this one will break.
+    pub linked_from: Vec<String>,
+}
+
+impl DeleteConfirm {
+    pub fn new(graph: &NoteGraph, path: PathBuf) -> Option<Self> {
+        let idx = graph.find_by_path(&path)?;
+        let note = graph.get_note(idx)?;
+        let mut linked_from: Vec<String> = graph
+            .backlinks(idx)
+            .into_iter()
+            .filter(|other| other.path != path)
+            .map(|other| other.title.clone())
+            .collect();
+        linked_from.sort_by_key(|title| title.to_lowercase());
+        Some(Self {
+            title: note.title.clone(),
+            path,
+            linked_from,
+        })
+    }
+}
+
+/// State of the popup listing deleted notes.
+#[derive(Debug, Default)]
+pub struct TrashView {
+    pub entries: Vec<TrashEntry>,
+    pub selected: usize,
+}
+
+impl TrashView {
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn selected(&self) -> Option<&TrashEntry> {
+        self.entries.get(self.selected)
+    }
+}
+
+/// A change that `u` can take back.
+#[derive(Debug)]
+pub enum Undo {
+    Trash(TrashEntry),
+    /// A rename, recorded as where the note went and what it was before.
+    Rename {
+        path: PathBuf,
+        old_path: PathBuf,
+        old_title: String,
+    },
+}
@@ -0,0 +1,152 @@
+use crate::app::FileRow;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A folder while building the tree: subfolders by name, then its notes.
+#[derive(Default)]
+struct Folder {
+    folders: BTreeMap<String, Folder>,
+    notes: Vec<PathBuf>,
+    count: usize,
+}
+
+impl Folder {
+    fn insert(&mut self, components: &[String], path: PathBuf) {
+        self.count += 1;
+        match components {
+            [] | [_] => self.notes.push(path),
+            [first, rest @ ..] => self
+                .folders
+                .entry(first.clone())
+                .or_default()
+                .insert(rest, path),
+        }
+    }
+
+    fn rows(
+        &self,
+        dir: &Path,
+        depth: usize,
+        expanded: &dyn Fn(&Path) -> bool,
+        rows: &mut Vec<FileRow>,
+    ) {
+        for (name, folder) in &self.folders {
+            let path = dir.join(name);
+            let is_expanded = expanded(&path);
+            rows.push(FileRow::Dir {
+                path: path.clone(),
+                depth,
+                count: folder.count,
+                expanded: is_expanded,
+            });
+            if is_expanded {
+                folder.rows(&path, depth + 1, expanded, rows);
+            }
+        }
+        rows.extend(self.notes.iter().map(|path| FileRow::Note {
+            path: path.clone(),
+            depth,
+        }));
+    }
+}
+
+/// Arrange notes under `root` into folder rows, folders first. Notes keep
+/// their given order within a folder; only expanded folders are descended.
+/// With `expand_all` every folder is open regardless of `expanded`.
+pub fn tree_rows(
+    root: &Path,
+    notes: &[PathBuf],
+    expanded: &HashSet<PathBuf>,
+    expand_all: bool,
+) -> Vec<FileRow> {
+    let mut top = Folder::default();
+    for path in notes {
+        let components: Vec<String> = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        top.insert(&components, path.clone());
+    }
+    let mut rows = Vec::new();
+    top.rows(
+        root,
+        0,
+        &|path| expand_all || expanded.contains(path),
+        &mut rows,
+    );
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(path: &str, depth: usize) -> FileRow {
+        FileRow::Note {
+            path: PathBuf::from(path),
+            depth,
+        }
+    }
+
+    fn dir(path: &str, depth: usize, count: usize, expanded: bool) -> FileRow {
+        FileRow::Dir {
+            path: PathBuf::from(path),
+            depth,
+            count,
+            expanded,
+        }
+    }
+
+    fn notes(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_tree_rows_nesting() {
+        let notes = notes(&["/v/b.md", "/v/work/x/deep.md", "/v/work/plan.md", "/v/a.md"]);
+        let expanded = HashSet::from([PathBuf::from("/v/work"), PathBuf::from("/v/work/x")]);
+
+        assert_eq!(
+            tree_rows(Path::new("/v"), &notes, &expanded, false),
+            vec![
+                dir("/v/work", 0, 2, true),
+                dir("/v/work/x", 1, 1, true),
+                note("/v/work/x/deep.md", 2),
+                note("/v/work/plan.md", 1),
+                // Notes keep the order they were sorted in
+                note("/v/b.md", 0),
+                note("/v/a.md", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_rows_collapsed() {
+        let notes = notes(&["/v/zoo/z.md", "/v/art/a.md", "/v/art/sub/s.md", "/v/top.md"]);
+        // An expanded folder inside a collapsed one stays hidden
+        let expanded = HashSet::from([PathBuf::from("/v/art/sub")]);
+
+        assert_eq!(
+            tree_rows(Path::new("/v"), &notes, &expanded, false),
+            vec![
+                dir("/v/art", 0, 2, false),
+                dir("/v/zoo", 0, 1, false),
+                note("/v/top.md", 0),
+            ]
+        );
+        assert_eq!(
+            tree_rows(Path::new("/v"), &notes, &expanded, true),
+            vec![
+                dir("/v/art", 0, 2, true),
+                dir("/v/art/sub", 1, 1, true),
+                note("/v/art/sub/s.md", 2),
+                note("/v/art/a.md", 1),
+                dir("/v/zoo", 0, 1, true),
+                note("/v/zoo/z.md", 1),
+                note("/v/top.md", 0),
+            ]
+        );
+    }
+}
@@ -1,11 +1,28 @@
-use crate::app::{App, Pane};
+use crate::app::{App, BacklinkEntry, FileRow, Pane, Popup};
+use crate::calendar::{Calendar, DayView};
+use crate::chain::{Arrow, LinkChain};
+use crate::completion::LinkCompletion;
+use crate::finder::{Finder, FinderAction};
+use crate::health::{HealthRow, HealthView};
+use crate::local_graph::LocalGraph;
+use crate::note_editor::{Mode, NoteEditor};
+use crate::rename::{PreviewRow, RenamePreview};
+use crate::search::SearchPanel;
+use crate::templates::TemplatePicker;
+use crate::trash::{DeleteConfirm, TrashView};
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style},
+    symbols,
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, Paragraph, Wrap,
+        canvas::{Canvas, Line as CanvasLine},
+    },
 };
+use tenki_core::fuzzy::MatchField;
+use tenki_core::{CompletionKind, Snippet};
 
 pub fn draw(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -26,17 +43,671 @@ pub fn draw(f: &mut Frame, app: &App) {
         ])
         .split(main_area);
 
-    // File explorer (left pane)
-    draw_file_explorer(f, app, panes[0]);
+    // File explorer (left pane), with the calendar below it when shown
+    match &app.calendar {
+        Some(calendar) => {
+            let left = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(10)])
+                .split(panes[0]);
+            draw_file_explorer(f, app, left[0]);
+            draw_calendar(f, app, calendar, left[1]);
+        }
+        None => draw_file_explorer(f, app, panes[0]),
+    }
 
-    // Markdown preview (center pane)
-    draw_preview(f, app, panes[1]);
+    // Markdown preview (center pane), or the note being edited
+    let editor_cursor = match &app.editor {
+        Some(editor) => draw_editor(f, app, editor, panes[1]),
+        None => {
+            draw_preview(f, app, panes[1]);
+            None
+        }
+    };
 
-    // Backlinks (right pane)
-    draw_backlinks(f, app, panes[2]);
+    // Backlinks and unlinked mentions (right pane)
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(45),
+            Constraint::Percentage(25),
+            Constraint::Percentage(30),
+        ])
+        .split(panes[2]);
+    draw_backlinks(f, app, right[0]);
+    draw_related(f, app, right[1]);
+    draw_mentions(f, app, right[2]);
 
     // Status bar
-    draw_status(f, app, status_area);
+    let prompt_cursor = draw_status(f, app, status_area);
+
+    // Popups draw over everything else
+    match &app.popup {
+        Some(Popup::Finder(finder)) => draw_finder(f, app, finder),
+        Some(Popup::Search(panel)) => draw_search(f, app, panel),
+        Some(Popup::Health(view)) => draw_health(f, app, view),
+        Some(Popup::Chain(chain)) => draw_chain(f, app, chain),
+        Some(Popup::Graph(view)) => draw_local_graph(f, app, view),
+        Some(Popup::Rename(preview)) => draw_rename(f, preview),
+        Some(Popup::Delete(confirm)) => draw_delete(f, app, confirm),
+        Some(Popup::Trash(view)) => draw_trash(f, view),
+        Some(Popup::Templates(picker)) => draw_templates(f, picker),
+        Some(Popup::Day(view)) => draw_day(f, view),
+        // The query prompt lives in the status bar
+        Some(Popup::Prompt(_)) | None => {}
+    }
+
+    // Link suggestions sit at the cursor of whatever is being typed in
+    if let Some(completion) = &app.completion
+        && let Some(cursor) = prompt_cursor.or(editor_cursor)
+    {
+        draw_completion(f, completion, cursor);
+    }
+}
+
+/// A rectangle of the given percentage size centred in `area`.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Style the characters of `text` at the given byte offsets.
+fn highlight_positions<'a>(text: &'a str, positions: &[usize], base: Style) -> Vec<Span<'a>> {
+    let matched = base.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    text.char_indices()
+        .map(|(i, c)| {
+            let style = if positions.contains(&i) {
+                matched
+            } else {
+                base
+            };
+            Span::styled(&text[i..i + c.len_utf8()], style)
+        })
+        .collect()
+}
+
+fn draw_finder(f: &mut Frame, app: &App, finder: &Finder) {
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let title = match &finder.action {
+        FinderAction::Open => " Find note ".to_string(),
+        FinderAction::ChainFrom(from) => {
+            format!(" Link chain from {} to… ", app.file_display_name(from))
+        }
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+
+    let prompt = Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Cyan)),
+        Span::raw(finder.input.value.as_str()),
+    ]);
+    f.render_widget(Paragraph::new(prompt), chunks[0]);
+    f.set_cursor_position((chunks[0].x + 2 + finder.input.cursor_column(), chunks[0].y));
+
+    let dim = Style::default().fg(Color::Gray);
+    let items: Vec<ListItem> = finder
+        .results
+        .iter()
+        .enumerate()
+        .map(|(i, found)| {
+            let style = if i == finder.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let path = app.file_display_name(&found.path);
+            let mut spans = Vec::new();
+            match &found.field {
+                MatchField::Title => {
+                    spans.extend(highlight_positions(&found.title, &found.positions, style));
+                    spans.push(Span::styled(format!("  {}", path), dim));
+                }
+                MatchField::Alias(alias) => {
+                    spans.push(Span::styled(found.title.as_str(), style));
+                    spans.push(Span::styled("  aka ", dim));
+                    spans.extend(highlight_positions(alias, &found.positions, dim));
+                }
+                MatchField::Path => {
+                    spans.push(Span::styled(found.title.as_str(), style));
+                    spans.push(Span::styled("  ", dim));
+                    spans.extend(
+                        highlight_positions(&path, &found.positions, dim)
+                            .into_iter()
+                            .map(|s| Span::styled(s.content.into_owned(), s.style)),
+                    );
+                }
+            }
+            ListItem::new(Line::from(spans)).style(style)
+        })
+        .collect();
+
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(finder.selected));
+    f.render_stateful_widget(List::new(items), chunks[1], &mut state);
+}
+
+fn draw_chain(f: &mut Frame, app: &App, chain: &LinkChain) {
+    let area = centered_rect(50, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let title = |idx| {
+        app.graph
+            .get_note(idx)
+            .map(|note| note.title.as_str())
+            .unwrap_or_default()
+    };
+    let mode = if chain.directed {
+        "following links"
+    } else {
+        "either direction"
+    };
+    let block = Block::default()
+        .title(format!(
+            " {} → {} · {mode} ",
+            title(chain.from),
+            title(chain.to)
+        ))
+        .title_bottom(" d: toggle direction ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    if chain.steps.is_empty() {
+        let text = if chain.directed {
+            "No chain of links between these notes. Press d to follow links either way."
+        } else {
+            "These notes aren't connected."
+        };
+        let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let dim = Style::default().fg(Color::Gray);
+    let items: Vec<ListItem> = chain
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let style = if i == chain.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let (arrow, hint) = match step.arrow {
+                None => ("  ", ""),
+                Some(Arrow::Forward) => ("→ ", ""),
+                Some(Arrow::Backward) => ("← ", "  links back"),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(arrow, Style::default().fg(Color::Cyan)),
+                Span::styled(step.title.as_str(), style),
+                Span::styled(format!("  {}", app.file_display_name(&step.path)), dim),
+                Span::styled(hint, Style::default().fg(Color::Yellow)),
+            ]))
+            .style(style)
+        })
+        .collect();
+
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(chain.selected));
+    f.render_stateful_widget(List::new(items).block(block), area, &mut state);
+}
+
+fn draw_local_graph(f: &mut Frame, app: &App, view: &LocalGraph) {
+    let area = centered_rect(80, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let center = app
+        .graph
+        .get_note(view.center)
+        .map(|note| note.title.as_str())
+        .unwrap_or_default();
+    let more = if view.hidden > 0 {
+        format!(" · {} more not shown", view.hidden)
+    } else {
+        String::new()
+    };
+    let selected = view
+        .nodes
+        .get(view.selected)
+        .map(|node| {
+            let degree = view.selected_degree();
+            let plural = if degree == 1 { "" } else { "s" };
+            format!(
+                " {} · {} · {degree} link{plural} ",
+                node.title,
+                app.file_display_name(&node.path)
+            )
+        })
+        .unwrap_or_default();
+    let block = Block::default()
+        .title(format!(
+            " Local graph · {center} · depth {}{more} ",
+            view.depth
+        ))
+        .title_bottom(Line::from(" hjkl: move  Enter: open  c: centre  d: depth ").left_aligned())
+        .title_bottom(Line::from(selected).right_aligned())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+
+    // Leave room around the edges for labels centred on outer nodes
+    let x_bounds = [-1.3, 1.3];
+    let y_bounds = [-1.15, 1.15];
+    let cell_width = (x_bounds[1] - x_bounds[0]) / f64::from(inner.width);
+    let canvas = Canvas::default()
+        .marker(symbols::Marker::Braille)
+        .x_bounds(x_bounds)
+        .y_bounds(y_bounds)
+        .paint(|ctx| {
+            for &(a, b) in &view.edges {
+                let touches_selected = a == view.selected || b == view.selected;
+                let (a, b) = (&view.nodes[a], &view.nodes[b]);
+                ctx.draw(&CanvasLine {
+                    x1: a.x,
+                    y1: a.y,
+                    x2: b.x,
+                    y2: b.y,
+                    color: if touches_selected {
+                        Color::Cyan
+                    } else {
+                        Color::DarkGray
+                    },
+                });
+            }
+            ctx.layer();
+            for (i, node) in view.nodes.iter().enumerate() {
+                let label: String = node.title.chars().take(24).collect();
+                let style = if i == view.selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else if i == 0 {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else if node.distance > 1 {
+                    Style::default().fg(Color::Gray)
+                } else {
+                    Style::default()
+                };
+                let half = label.chars().count() as f64 / 2.0 * cell_width;
+                let x = (node.x - half).max(x_bounds[0]);
+                ctx.print(x, node.y, Span::styled(label, style));
+            }
+        });
+    f.render_widget(canvas, inner);
+}
+
+fn draw_rename(f: &mut Frame, preview: &RenamePreview) {
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let plan = &preview.plan;
+    let links = plan.link_count();
+    let block = Block::default()
+        .title(format!(
+            " Rename · {} → {} · {links} link{} ",
+            plan.old_title,
+            plan.new_title,
+            if links == 1 { "" } else { "s" }
+        ))
+        .title_bottom(" Enter: apply  Esc: cancel  j/k: scroll ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let lines: Vec<Line> = preview
+        .rows
+        .iter()
+        .map(|row| match row {
+            PreviewRow::Heading(text) => Line::from(Span::styled(
+                text.as_str(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            PreviewRow::Removed { line, text } => Line::from(vec![
+                Span::styled(format!("{line:>5} "), dim),
+                Span::styled(format!("- {text}"), Style::default().fg(Color::Red)),
+            ]),
+            PreviewRow::Added { line, text } => Line::from(vec![
+                Span::styled(format!("{line:>5} "), dim),
+                Span::styled(format!("+ {text}"), Style::default().fg(Color::Green)),
+            ]),
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((preview.scroll as u16, 0));
+    f.render_widget(paragraph, area);
+}
+
+fn draw_delete(f: &mut Frame, app: &App, confirm: &DeleteConfirm) {
+    let area = centered_rect(50, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Move to trash ")
+        .title_bottom(" Enter: delete  Esc: cancel ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("Move "),
+            Span::styled(
+                confirm.title.as_str(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" ({})", app.file_display_name(&confirm.path)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::raw(" to the trash?"),
+        ]),
+        Line::from(""),
+    ];
+    if confirm.linked_from.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No other notes link to it.",
+            Style::default().fg(Color::Gray),
+        )));
+    } else {
+        let count = confirm.linked_from.len();
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Links from {count} note{} will break:",
+                if count == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+        for title in &confirm.linked_from {
+            lines.push(Line::from(format!("  {title}")));
+        }
+    }
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn draw_trash(f: &mut Frame, view: &TrashView) {
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Trash ({}) ", view.entries.len()))
+        .title_bottom(" Enter: restore  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let dim = Style::default().fg(Color::Gray);
+    let items: Vec<ListItem> = view
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let style = if i == view.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(entry.title.as_str(), style),
+                Span::styled(format!("  {}", entry.path.display()), dim),
+                Span::styled(
+                    format!("  deleted {}", entry.deleted),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+            .style(style)
+        })
+        .collect();
+
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(view.selected));
+    f.render_stateful_widget(List::new(items).block(block), area, &mut state);
+}
+
+fn draw_templates(f: &mut Frame, picker: &TemplatePicker) {
+    let area = centered_rect(50, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" New note: {} ", picker.title))
+        .title_bottom(" Enter: create  Esc: cancel ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let names =
+        std::iter::once("Blank note").chain(picker.templates.iter().map(|t| t.name.as_str()));
+    let items: Vec<ListItem> = names
+        .enumerate()
+        .map(|(i, name)| {
+            let style = if i == picker.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else if i == 0 {
+                Style::default().fg(Color::Gray)
+            } else {
+                Style::default()
+            };
+            ListItem::new(name).style(style)
+        })
+        .collect();
+
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(picker.selected));
+    f.render_stateful_widget(List::new(items).block(block), area, &mut state);
+}
+
+fn draw_health(f: &mut Frame, app: &App, view: &HealthView) {
+    let area = centered_rect(60, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(" Vault health · {} ", view.summary))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let dim = Style::default().fg(Color::Gray);
+    let items: Vec<ListItem> = view
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| match row {
+            HealthRow::Heading(text) => ListItem::new(Line::from(Span::styled(
+                text.as_str(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ))),
+            HealthRow::Info(text) => ListItem::new(Line::from(Span::styled(
+                format!("  {text}"),
+                Style::default().fg(Color::DarkGray),
+            ))),
+            HealthRow::Note {
+                path,
+                title,
+                detail,
+            } => {
+                let style = if i == view.selected {
+                    Style::default()
+                        .bg(Color::DarkGray)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let mut spans = vec![
+                    Span::styled(format!("  {title}"), style),
+                    Span::styled(format!("  {}", app.file_display_name(path)), dim),
+                ];
+                if let Some(detail) = detail {
+                    spans.push(Span::styled(
+                        format!("  {detail}"),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                ListItem::new(Line::from(spans)).style(style)
+            }
+        })
+        .collect();
+
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(view.selected));
+    f.render_stateful_widget(List::new(items).block(block), area, &mut state);
+}
+
+fn draw_calendar(f: &mut Frame, app: &App, calendar: &Calendar, area: Rect) {
+    let is_active = app.active_pane == Pane::Calendar;
+    let border_style = if is_active {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let block = Block::default()
+        .title(format!(" {} ", calendar.cursor.format("%B %Y")))
+        .borders(Borders::ALL)
+        .border_style(border_style);
+
+    let today = chrono::Local::now().date_naive();
+    let mut lines = vec![Line::styled(
+        "Mo Tu We Th Fr Sa Su",
+        Style::default().fg(Color::Gray),
+    )];
+    for week in calendar.weeks() {
+        let mut spans = Vec::new();
+        for day in week {
+            let Some(day) = day else {
+                spans.push(Span::raw("   "));
+                continue;
+            };
+            // Daily notes in green, other edits in yellow, due tasks in red
+            let mut style = match calendar.activity.get(&day) {
+                Some(activity) if !activity.due.is_empty() => Style::default().fg(Color::Red),
+                Some(activity) if activity.journal.is_some() => Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+                Some(_) => Style::default().fg(Color::Yellow),
+                None => Style::default().fg(Color::DarkGray),
+            };
+            if day == today {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if day == calendar.cursor {
+                style = style.bg(if is_active {
+                    Color::Cyan
+                } else {
+                    Color::DarkGray
+                });
+                if is_active {
+                    style = style.fg(Color::Black);
+                }
+            }
+            spans.push(Span::styled(format!("{:>2}", day.format("%-d")), style));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let mut summary = vec![calendar.cursor.format("%a %-d").to_string()];
+    if let Some(activity) = calendar.selected() {
+        if activity.journal.is_some() {
+            summary.push("journal".to_string());
+        }
+        if !activity.created.is_empty() {
+            summary.push(format!("{} new", activity.created.len()));
+        }
+        if !activity.modified.is_empty() {
+            summary.push(format!("{} edited", activity.modified.len()));
+        }
+        if !activity.due.is_empty() {
+            summary.push(format!("{} due", activity.due.len()));
+        }
+    }
+    let inner_height = area.height.saturating_sub(2) as usize;
+    while lines.len() + 1 < inner_height {
+        lines.push(Line::default());
+    }
+    lines.push(Line::styled(
+        summary.join(" · "),
+        Style::default().fg(Color::Gray),
+    ));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_day(f: &mut Frame, view: &DayView) {
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(format!(
+            " {} ({}) ",
+            view.date.format("%A %-d %B %Y"),
+            view.rows.len()
+        ))
+        .title_bottom(" Enter: open  Esc: close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let items: Vec<ListItem> = view
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let style = if i == view.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(row.title.as_str(), style),
+                Span::styled(
+                    format!("  {}", row.reason),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]))
+            .style(style)
+        })
+        .collect();
+
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(view.selected));
+    f.render_stateful_widget(List::new(items).block(block), area, &mut state);
 }
 
 fn draw_file_explorer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
@@ -48,11 +719,51 @@ fn draw_file_explorer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     };
 
     let items: Vec<ListItem> = app
-        .files
+        .view
         .iter()
         .enumerate()
-        .map(|(i, path)| {
-            let name = app.file_display_name(path);
+        .map(|(i, row)| {
+            let line = match row {
+                FileRow::Saved {
+                    index,
+                    count,
+                    expanded,
+                } => {
+                    let marker = if *expanded { "▾" } else { "▸" };
+                    let count = count.map_or("!".to_string(), |n| n.to_string());
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{marker} {}", app.config.saved_searches[*index].name),
+                            Style::default().fg(Color::Magenta),
+                        ),
+                        Span::styled(format!(" ({count})"), Style::default().fg(Color::DarkGray)),
+                    ])
+                }
+                FileRow::SavedNote(path) => {
+                    Line::from(format!("  {}", app.file_display_name(path)))
+                }
+                FileRow::Dir {
+                    path,
+                    depth,
+                    count,
+                    expanded,
+                } => {
+                    let marker = if *expanded { "▾" } else { "▸" };
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}{marker} {name}/", "  ".repeat(*depth)),
+                            Style::default().fg(Color::Blue),
+                        ),
+                        Span::styled(format!(" ({count})"), Style::default().fg(Color::DarkGray)),
+                    ])
+                }
+                FileRow::Note { path, depth } if app.tree_view => {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    Line::from(format!("{}{name}", "  ".repeat(*depth)))
+                }
+                FileRow::Note { path, .. } => Line::from(app.file_display_name(path)),
+            };
             let style = if i == app.file_list_state {
                 Style::default()
                     .bg(Color::DarkGray)
@@ -60,12 +771,23 @@ fn draw_file_explorer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             } else {
                 Style::default()
             };
-            ListItem::new(name).style(style)
+            ListItem::new(line).style(style)
         })
         .collect();
 
+    let mut title = match &app.query {
+        Some(query) => format!(" Query: {} ", query),
+        None => " Files ".to_string(),
+    };
+    if !app.filter.is_empty() {
+        title.push_str(&format!("/{}/ ", app.filter));
+    }
+    if app.query.is_some() || !app.filter.is_empty() {
+        title.push_str(&format!("({}) ", app.note_count()));
+    }
+    title.push_str(&format!("· {} ", app.sort_label()));
     let files_block = Block::default()
-        .title(" Files ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(border_style);
 
@@ -74,6 +796,104 @@ fn draw_file_explorer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(list, area);
 }
 
+/// Draw the note being edited, returning where the cursor is.
+fn draw_editor(f: &mut Frame, app: &App, editor: &NoteEditor, area: Rect) -> Option<Position> {
+    let modified = if editor.is_modified() { " [+]" } else { "" };
+    let block = Block::default()
+        .title(format!(
+            " {}{modified} ",
+            app.file_display_name(&editor.path)
+        ))
+        .title_bottom(format!(" {}:{} ", editor.row + 1, editor.col + 1))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    let (top, left) = editor.scroll_into_view(inner.height as usize, inner.width as usize);
+
+    let lines: Vec<Line> = editor
+        .lines
+        .iter()
+        .skip(top)
+        .take(inner.height as usize)
+        .map(|line| {
+            // Headings stand out as in the preview
+            if line.starts_with('#') {
+                Line::styled(
+                    line.as_str(),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Line::raw(line.as_str())
+            }
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines).block(block).scroll((0, left as u16));
+    f.render_widget(paragraph, area);
+
+    if editor.mode == Mode::Command || app.popup.is_some() {
+        return None;
+    }
+    let cursor = Position::new(
+        inner.x + (editor.col - left) as u16,
+        inner.y + (editor.row - top) as u16,
+    );
+    f.set_cursor_position(cursor);
+    Some(cursor)
+}
+
+/// Draw the suggestions for a link being typed next to the cursor: below it
+/// when there is room, otherwise above.
+fn draw_completion(f: &mut Frame, completion: &LinkCompletion, cursor: Position) {
+    let screen = f.area();
+    let width = 50.min(screen.width);
+    let height = (completion.items.len() as u16 + 2).min(screen.height);
+    let y = if cursor.y + 1 + height <= screen.bottom() {
+        cursor.y + 1
+    } else {
+        cursor.y.saturating_sub(height)
+    };
+    let area = Rect {
+        x: cursor.x.min(screen.right() - width),
+        y,
+        width,
+        height,
+    };
+    f.render_widget(Clear, area);
+
+    let dim = Style::default().fg(Color::Gray);
+    let items: Vec<ListItem> = completion
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let style = if i == completion.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let mut spans = highlight_positions(&item.label, &item.positions, style);
+            match item.kind {
+                CompletionKind::Title => {}
+                CompletionKind::Alias => {
+                    spans.push(Span::styled(format!("  → {}", item.title), dim))
+                }
+                CompletionKind::Heading => spans.insert(0, Span::styled("# ", dim)),
+            }
+            ListItem::new(Line::from(spans)).style(style)
+        })
+        .collect();
+    let block = Block::default()
+        .title(" Link ")
+        .title_bottom(" Tab:insert  Esc:close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    f.render_widget(List::new(items).block(block), area);
+}
+
 fn draw_preview(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let is_active = app.active_pane == Pane::Preview;
     let border_style = if is_active {
@@ -82,29 +902,98 @@ fn draw_preview(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         Style::default().fg(Color::DarkGray)
     };
 
-    let title = app
-        .selected_file()
-        .map(|p| app.file_display_name(&p))
-        .unwrap_or_else(|| "No file selected".to_string());
+    let title = match app.view.get(app.file_list_state) {
+        Some(FileRow::Saved { .. }) => "Saved search".to_string(),
+        _ => app
+            .selected_file()
+            .map(|p| app.file_display_name(&p))
+            .unwrap_or_else(|| "No file selected".to_string()),
+    };
 
     let preview_block = Block::default()
         .title(format!(" {} ", title))
         .borders(Borders::ALL)
         .border_style(border_style);
 
-    let content = if app.selected_content.is_empty() {
-        "Select a file to preview its contents."
-    } else {
-        &app.selected_content
-    };
+    if app.selected_content.is_empty() {
+        let paragraph = Paragraph::new("Select a file to preview its contents.")
+            .block(preview_block)
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    let selected = is_active
+        .then(|| app.preview_links.get(app.link_list_state))
+        .flatten();
+
+    // Keep the selected link (or jump target) roughly centred once it would
+    // scroll off screen
+    let height = area.height.saturating_sub(2) as usize;
+    let scroll = selected
+        .map(|link| {
+            app.selected_content[..link.span.start]
+                .matches('\n')
+                .count()
+        })
+        .or(app.jump_line.map(|line| line.saturating_sub(1)))
+        .filter(|&line| line >= height)
+        .map_or(0, |line| line - height / 2);
 
-    let paragraph = Paragraph::new(content)
+    let mut lines = preview_lines(app, selected.map(|l| l.span.clone()));
+    if let Some(line) = app
+        .jump_line
+        .and_then(|l| lines.get_mut(l.saturating_sub(1)))
+    {
+        line.style = Style::default().bg(Color::DarkGray);
+    }
+
+    let paragraph = Paragraph::new(lines)
         .block(preview_block)
-        .wrap(Wrap { trim: false });
+        .wrap(Wrap { trim: false })
+        .scroll((scroll as u16, 0));
 
     f.render_widget(paragraph, area);
 }
 
+/// Split the previewed note into lines with wikilinks highlighted.
+fn preview_lines(app: &App, selected: Option<std::ops::Range<usize>>) -> Vec<Line<'_>> {
+    let content = &app.selected_content;
+    let link_style = Style::default().fg(Color::Cyan);
+    let selected_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for raw in content.split_inclusive('\n') {
+        let line_end = offset + raw.len();
+        let text = raw.trim_end_matches(['\n', '\r']);
+        let mut spans = Vec::new();
+        let mut pos = 0;
+
+        for link in app
+            .preview_links
+            .iter()
+            .filter(|l| l.span.start >= offset && l.span.end <= line_end)
+        {
+            let start = link.span.start - offset;
+            let end = (link.span.end - offset).min(text.len());
+            spans.push(Span::raw(&text[pos..start]));
+            let style = if selected.as_ref() == Some(&link.span) {
+                selected_style
+            } else {
+                link_style
+            };
+            spans.push(Span::styled(&text[start..end], style));
+            pos = end;
+        }
+        spans.push(Span::raw(&text[pos..]));
+
+        lines.push(Line::from(spans));
+        offset = line_end;
+    }
+    lines
+}
+
 fn draw_backlinks(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let is_active = app.active_pane == Pane::Backlinks;
     let border_style = if is_active {
@@ -113,11 +1002,14 @@ fn draw_backlinks(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         Style::default().fg(Color::DarkGray)
     };
 
+    // Width available for snippet text inside the borders
+    let width = area.width.saturating_sub(2) as usize;
+
     let items: Vec<ListItem> = app
         .backlinks
         .iter()
         .enumerate()
-        .map(|(i, title)| {
+        .map(|(i, backlink)| {
             let style = if i == app.backlink_list_state {
                 Style::default()
                     .bg(Color::DarkGray)
@@ -125,7 +1017,7 @@ fn draw_backlinks(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             } else {
                 Style::default()
             };
-            ListItem::new(title.as_str()).style(style)
+            ListItem::new(backlink_lines(backlink, width)).style(style)
         })
         .collect();
 
@@ -142,7 +1034,310 @@ fn draw_backlinks(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(list, area);
 }
 
-fn draw_status(f: &mut Frame, _app: &App, area: ratatui::layout::Rect) {
+/// Render a backlink as its title followed by the context of each occurrence.
+fn backlink_lines(backlink: &BacklinkEntry, width: usize) -> Vec<Line<'_>> {
+    let mut lines = vec![Line::from(backlink.title.as_str())];
+
+    let shown = if backlink.expanded {
+        backlink.snippets.len()
+    } else {
+        1
+    };
+    for snippet in backlink.snippets.iter().take(shown) {
+        lines.push(snippet_line(snippet, width));
+    }
+
+    let hidden = backlink.snippets.len().saturating_sub(shown);
+    if hidden > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("  +{} more (Space to expand)", hidden),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+
+    lines
+}
+
+fn draw_related(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let is_active = app.active_pane == Pane::Related;
+    let border_style = if is_active {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    let items: Vec<ListItem> = app
+        .related
+        .iter()
+        .enumerate()
+        .map(|(i, related)| {
+            let style = if i == app.related_list_state {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(vec![
+                Line::from(related.title.as_str()),
+                Line::from(Span::styled(
+                    format!("  {}", related.reason),
+                    Style::default().fg(Color::Gray),
+                )),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let related_block = Block::default()
+        .title(format!(" Related ({}) ", app.related.len()))
+        .borders(Borders::ALL)
+        .border_style(border_style);
+
+    let list = List::new(items).block(related_block);
+
+    f.render_widget(list, area);
+}
+
+fn draw_mentions(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let is_active = app.active_pane == Pane::Mentions;
+    let border_style = if is_active {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    let width = area.width.saturating_sub(2) as usize;
+
+    let items: Vec<ListItem> = app
+        .mentions
+        .iter()
+        .enumerate()
+        .map(|(i, mention)| {
+            let style = if i == app.mention_list_state {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let source = app
+                .graph
+                .find_by_path(&mention.path)
+                .and_then(|idx| app.graph.get_note(idx))
+                .map(|note| note.title.clone())
+                .unwrap_or_else(|| app.file_display_name(&mention.path));
+            ListItem::new(vec![
+                Line::from(source),
+                snippet_line(&mention.snippet, width),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    // Searching reads the whole vault, so it waits until the pane is focused
+    let (title, items) = if app.mentions_for.is_none() && app.selected_file().is_some() {
+        let hint = Span::styled(
+            "Focus to search the vault",
+            Style::default().fg(Color::DarkGray),
+        );
+        (" Unlinked mentions ".to_string(), vec![ListItem::new(hint)])
+    } else {
+        (
+            format!(" Unlinked mentions ({}) ", app.mentions.len()),
+            items,
+        )
+    };
+
+    let mentions_block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(border_style);
+
+    let list = List::new(items).block(mentions_block);
+
+    f.render_widget(list, area);
+}
+
+fn draw_search(f: &mut Frame, app: &App, panel: &SearchPanel) {
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let title = format!(
+        " Search ({} notes, {} lines) ",
+        panel.hits.len(),
+        panel.entries.len()
+    );
+    let order = if panel.by_importance {
+        "importance"
+    } else {
+        "relevance"
+    };
+    let block = Block::default()
+        .title(title)
+        .title_bottom(format!(" by {order} · ^s: toggle "))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+
+    let prompt = Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Cyan)),
+        Span::raw(panel.input.value.as_str()),
+    ]);
+    f.render_widget(Paragraph::new(prompt), chunks[0]);
+    f.set_cursor_position((chunks[0].x + 2 + panel.input.cursor_column(), chunks[0].y));
+
+    let hint = match &panel.error {
+        Some(error) => Span::styled(error.as_str(), Style::default().fg(Color::Red)),
+        None => Span::styled(
+            "terms, \"phrases\", prefix*, AND/OR/NOT, -term, ( )",
+            Style::default().fg(Color::Gray),
+        ),
+    };
+    f.render_widget(Paragraph::new(Line::from(hint)), chunks[1]);
+
+    let width = chunks[2].width as usize;
+    let items: Vec<ListItem> = panel
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, &(h, l))| {
+            let hit = &panel.hits[h];
+            let style = if i == panel.selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let mut lines = Vec::new();
+            // Show the note title above its first matching line
+            if l == 0 {
+                let title = app
+                    .graph
+                    .find_by_path(&hit.path)
+                    .and_then(|idx| app.graph.get_note(idx))
+                    .map(|note| note.title.clone())
+                    .unwrap_or_else(|| app.file_display_name(&hit.path));
+                lines.push(Line::from(Span::styled(
+                    title,
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+            lines.push(snippet_line(&hit.lines[l], width));
+            ListItem::new(lines).style(style)
+        })
+        .collect();
+
+    // Scroll the list so the selection stays visible
+    let mut state = ratatui::widgets::ListState::default().with_selected(Some(panel.selected));
+    f.render_stateful_widget(List::new(items), chunks[2], &mut state);
+}
+
+/// Render a snippet as `line: text`, keeping the highlighted part in view.
+fn snippet_line(snippet: &Snippet, width: usize) -> Line<'_> {
+    let prefix = format!("  {}: ", snippet.line);
+    let avail = width.saturating_sub(prefix.len());
+    let text = &snippet.text;
+
+    // Keep the highlight in view when the line is too long
+    let mut start = if text.chars().count() <= avail {
+        0
+    } else {
+        snippet.highlight.start.saturating_sub(avail / 3)
+    };
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let hl_start = snippet.highlight.start.max(start);
+    let hl_end = snippet.highlight.end.max(hl_start);
+
+    Line::from(vec![
+        Span::styled(prefix, Style::default().fg(Color::Gray)),
+        Span::raw(&text[start..hl_start]),
+        Span::styled(&text[hl_start..hl_end], Style::default().fg(Color::Yellow)),
+        Span::raw(&text[hl_end..]),
+    ])
+}
+
+/// Draw the status bar, returning where the cursor is if a prompt is open.
+fn draw_status(f: &mut Frame, app: &App, area: ratatui::layout::Rect) -> Option<Position> {
+    if let Some(Popup::Prompt(prompt)) = &app.popup {
+        let label = format!("{} ", app.prompt_label(prompt));
+        let offset = label.chars().count() as u16 + 1;
+        let spans = vec![
+            Span::styled(label, Style::default().fg(Color::Black).bg(Color::Yellow)),
+            Span::raw(" "),
+            Span::raw(prompt.input.value.as_str()),
+        ];
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+        let cursor = Position::new(area.x + offset + prompt.input.cursor_column(), area.y);
+        f.set_cursor_position(cursor);
+        if let Some(message) = &app.message {
+            let width = (message.chars().count() as u16 + 1).min(area.width / 2);
+            let error_area = Rect {
+                x: area.x + area.width - width,
+                width,
+                ..area
+            };
+            let error = Paragraph::new(Span::styled(
+                message.as_str(),
+                Style::default().fg(Color::Red),
+            ));
+            f.render_widget(error, error_area);
+        }
+        return Some(cursor);
+    }
+
+    if let Some(editor) = &app.editor {
+        draw_editor_status(f, app, editor, area);
+        return None;
+    }
+
+    let crumbs = app.breadcrumbs(4);
+    let crumb_width = if crumbs.len() > 1 {
+        let text = crumbs.join(" › ");
+        let width = (text.chars().count() as u16 + 2).min(area.width / 2);
+        let crumb_area = ratatui::layout::Rect {
+            x: area.x + area.width - width,
+            width,
+            ..area
+        };
+        let paragraph = Paragraph::new(Line::from(Span::styled(
+            text,
+            Style::default().fg(Color::Gray),
+        )))
+        .alignment(ratatui::layout::Alignment::Right);
+        f.render_widget(paragraph, crumb_area);
+        width
+    } else {
+        0
+    };
+    let area = ratatui::layout::Rect {
+        width: area.width - crumb_width,
+        ..area
+    };
+
+    if let Some(message) = &app.message {
+        let paragraph = Paragraph::new(Line::from(vec![
+            Span::styled(" tenki ", Style::default().fg(Color::Black).bg(Color::Cyan)),
+            Span::raw(" "),
+            Span::raw(message.as_str()),
+        ]));
+        f.render_widget(paragraph, area);
+        return None;
+    }
+
     let status = Line::from(vec![
         Span::styled(" tenki ", Style::default().fg(Color::Black).bg(Color::Cyan)),
         Span::raw(" "),
@@ -150,14 +1345,87 @@ fn draw_status(f: &mut Frame, _app: &App, area: ratatui::layout::Rect) {
         Span::raw(":quit "),
         Span::styled("e", Style::default().fg(Color::Yellow)),
         Span::raw(":edit "),
+        Span::styled("a", Style::default().fg(Color::Yellow)),
+        Span::raw(":new "),
+        Span::styled("R", Style::default().fg(Color::Yellow)),
+        Span::raw(":rename "),
+        Span::styled("D", Style::default().fg(Color::Yellow)),
+        Span::raw(":delete "),
+        Span::styled("u", Style::default().fg(Color::Yellow)),
+        Span::raw(":undo "),
         Span::styled("Tab", Style::default().fg(Color::Yellow)),
         Span::raw(":switch pane "),
         Span::styled("j/k", Style::default().fg(Color::Yellow)),
         Span::raw(":navigate "),
+        Span::styled("^o/M-→", Style::default().fg(Color::Yellow)),
+        Span::raw(":back/fwd "),
+        Span::styled("^p", Style::default().fg(Color::Yellow)),
+        Span::raw(":find "),
+        Span::styled("^f", Style::default().fg(Color::Yellow)),
+        Span::raw(":search "),
+        Span::styled(":", Style::default().fg(Color::Yellow)),
+        Span::raw(":query "),
+        Span::styled("S", Style::default().fg(Color::Yellow)),
+        Span::raw(":save query "),
+        Span::styled("f", Style::default().fg(Color::Yellow)),
+        Span::raw(":filter "),
+        Span::styled("s/o", Style::default().fg(Color::Yellow)),
+        Span::raw(":sort/order "),
+        Span::styled("h/l", Style::default().fg(Color::Yellow)),
+        Span::raw(":fold "),
+        Span::styled("t", Style::default().fg(Color::Yellow)),
+        Span::raw(":tree/flat "),
+        Span::styled("P", Style::default().fg(Color::Yellow)),
+        Span::raw(":link chain "),
+        Span::styled("H", Style::default().fg(Color::Yellow)),
+        Span::raw(":health "),
+        Span::styled("G", Style::default().fg(Color::Yellow)),
+        Span::raw(":graph "),
+        Span::styled("L", Style::default().fg(Color::Yellow)),
+        Span::raw(":link mention "),
         Span::styled("r", Style::default().fg(Color::Yellow)),
         Span::raw(":refresh"),
     ]);
 
     let paragraph = Paragraph::new(status);
     f.render_widget(paragraph, area);
+    None
+}
+
+fn draw_editor_status(f: &mut Frame, app: &App, editor: &NoteEditor, area: Rect) {
+    if editor.mode == Mode::Command {
+        let line = Line::from(vec![
+            Span::raw(":"),
+            Span::raw(editor.command.value.as_str()),
+        ]);
+        f.render_widget(Paragraph::new(line), area);
+        f.set_cursor_position((area.x + 1 + editor.command.cursor_column(), area.y));
+        return;
+    }
+    let (label, color) = match editor.mode {
+        Mode::Insert => (" INSERT ", Color::Green),
+        _ => (" NORMAL ", Color::Cyan),
+    };
+    let mut spans = vec![
+        Span::styled(label, Style::default().fg(Color::Black).bg(color)),
+        Span::raw(" "),
+    ];
+    match &app.message {
+        Some(message) => spans.push(Span::raw(message.as_str())),
+        None => {
+            for (key, action) in [
+                ("i/a/o", ":insert "),
+                ("Esc", ":normal "),
+                ("u/^r", ":undo/redo "),
+                (":w/^s", ":save "),
+                (":q", ":close "),
+                (":q!", ":discard "),
+                (":e!", ":reload"),
+            ] {
+                spans.push(Span::styled(key, Style::default().fg(Color::Yellow)));
+                spans.push(Span::raw(action));
+            }
+        }
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
 }